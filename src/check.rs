@@ -0,0 +1,368 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    color::{Color, RgbColor},
+    contrast::{apca_contrast, wcag_ratio},
+    error::CheckError,
+    format::Theme,
+    groups::CATEGORIES,
+    highlight::{Highlight, HighlightColor, Style},
+    vision::{delta_e, Deficiency},
+    CheckArgs,
+};
+
+const DISTINCT_GROUPS: [(&str, &str); 18] = [
+    ("DiffAdd", "DiffDelete"),
+    ("DiffAdd", "DiffChange"),
+    ("DiffChange", "DiffDelete"),
+    ("Added", "Removed"),
+    ("Added", "Changed"),
+    ("Changed", "Removed"),
+    ("diffAdded", "diffRemoved"),
+    ("GitSignsAdd", "GitSignsDelete"),
+    ("GitSignsAdd", "GitSignsChange"),
+    ("GitSignsChange", "GitSignsDelete"),
+    ("DiagnosticError", "DiagnosticWarn"),
+    ("DiagnosticError", "DiagnosticInfo"),
+    ("DiagnosticError", "DiagnosticHint"),
+    ("DiagnosticWarn", "DiagnosticInfo"),
+    ("DiagnosticWarn", "DiagnosticHint"),
+    ("DiagnosticInfo", "DiagnosticHint"),
+    ("DiagnosticOk", "DiagnosticError"),
+    ("DiagnosticOk", "DiagnosticWarn"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct EffectiveColors {
+    pub fg: RgbColor,
+    pub bg: RgbColor,
+}
+
+/// Returns `None` for link cycles or when neither the group nor `Normal` sets a color.
+pub(crate) fn effective_colors(
+    group: &str,
+    highlights: &HashMap<&str, &Highlight>,
+) -> Option<EffectiveColors> {
+    let mut visited = HashSet::new();
+    let mut highlight = *highlights.get(group)?;
+
+    while let Some(link) = &highlight.link {
+        if !visited.insert(link.as_str()) {
+            return None;
+        }
+
+        highlight = highlights.get(link.as_str())?;
+    }
+
+    let normal = highlights.get("Normal");
+    let normal_fg = normal.and_then(|normal| normal.fg.and_then(|fg| fg.rgb()));
+    let normal_bg = normal.and_then(|normal| normal.bg.and_then(|bg| bg.rgb()));
+
+    // The special `fg` and `bg` values refer to the colors of the Normal group
+    let resolve = |color: Option<HighlightColor>, fallback: Option<RgbColor>| match color {
+        Some(HighlightColor::Foreground) => normal_fg,
+        Some(HighlightColor::Background) => normal_bg,
+        color => color.and_then(|color| color.rgb()).or(fallback),
+    };
+
+    let fg = resolve(highlight.fg, normal_fg)?;
+    let bg = resolve(highlight.bg, normal_bg)?;
+
+    match highlight.has_style(Style::Reverse) {
+        true => Some(EffectiveColors { fg: bg, bg: fg }),
+        false => Some(EffectiveColors { fg, bg }),
+    }
+}
+
+pub(crate) fn check(theme: &Theme, args: &CheckArgs) -> Result<(), CheckError> {
+    let highlights: HashMap<&str, &Highlight> = theme
+        .highlights
+        .iter()
+        .map(|highlight| (highlight.group.as_str(), highlight))
+        .collect();
+
+    let contrast = check_contrast(theme, &highlights, args);
+    let vision = match args.cvd {
+        true => check_vision(&highlights, args),
+        false => 0,
+    };
+
+    if args.coverage {
+        report_coverage(&highlights);
+    }
+
+    if contrast > 0 || vision > 0 {
+        return Err(CheckError::Issues { contrast, vision });
+    }
+
+    Ok(())
+}
+
+/// Linked groups are skipped since their target is checked on its own.
+fn check_contrast(
+    theme: &Theme,
+    highlights: &HashMap<&str, &Highlight>,
+    args: &CheckArgs,
+) -> usize {
+    let mut checked = 0;
+    let mut failures = 0;
+
+    for highlight in &theme.highlights {
+        if highlight.link.is_some() || args.ignore.contains(&highlight.group) {
+            continue;
+        }
+
+        let Some(colors) = effective_colors(&highlight.group, highlights) else {
+            println!(
+                "{}: skipped, colors can't be resolved without a Normal group",
+                highlight.group
+            );
+            continue;
+        };
+
+        checked += 1;
+
+        let ratio = wcag_ratio(&colors.fg, &colors.bg);
+        let lc = apca_contrast(&colors.fg, &colors.bg);
+
+        let mut below = Vec::new();
+
+        if ratio < args.min_contrast {
+            below.push(format!("{:.2}:1", args.min_contrast));
+        }
+
+        if let Some(min_apca) = args.min_apca.filter(|min_apca| lc.abs() < *min_apca) {
+            below.push(format!("Lc {min_apca:.1}"));
+        }
+
+        if !below.is_empty() {
+            failures += 1;
+        }
+
+        if !below.is_empty() || args.verbose {
+            let status = match below.is_empty() {
+                true => "ok".to_string(),
+                false => format!("below {}", below.join(" and ")),
+            };
+
+            println!(
+                "{}: contrast {ratio:.2}:1, APCA Lc {lc:.1} (fg {}, bg {}) {status}",
+                highlight.group,
+                colors.fg.hex(),
+                colors.bg.hex()
+            );
+        }
+    }
+
+    if failures == 0 {
+        println!("All {checked} highlight groups meet the minimum contrast");
+    }
+
+    failures
+}
+
+/// A pair is distinguishable if either the foregrounds or the backgrounds differ enough.
+fn check_vision(highlights: &HashMap<&str, &Highlight>, args: &CheckArgs) -> usize {
+    let mut checked = 0;
+    let mut failures = 0;
+
+    for (group1, group2) in DISTINCT_GROUPS {
+        if [group1, group2]
+            .iter()
+            .any(|group| args.ignore.iter().any(|ignore| ignore == group))
+        {
+            continue;
+        }
+
+        let (Some(colors1), Some(colors2)) = (
+            effective_colors(group1, highlights),
+            effective_colors(group2, highlights),
+        ) else {
+            continue;
+        };
+
+        checked += 1;
+
+        let difference = |simulate: &dyn Fn(&RgbColor) -> RgbColor| {
+            delta_e(&simulate(&colors1.fg), &simulate(&colors2.fg))
+                .max(delta_e(&simulate(&colors1.bg), &simulate(&colors2.bg)))
+        };
+
+        let mut differences = vec![("normal vision".to_string(), difference(&|color| *color))];
+
+        for deficiency in Deficiency::ALL {
+            differences.push((
+                deficiency.to_string(),
+                difference(&|color| deficiency.simulate(color)),
+            ));
+        }
+
+        let indistinguishable: Vec<String> = differences
+            .iter()
+            .filter(|(_, difference)| *difference < args.min_delta_e)
+            .map(|(vision, difference)| format!("{vision} (ΔE {difference:.1})"))
+            .collect();
+
+        if !indistinguishable.is_empty() {
+            failures += 1;
+            println!(
+                "{group1} and {group2}: hard to distinguish with {}",
+                indistinguishable.join(", ")
+            );
+        } else if args.verbose {
+            let (vision, difference) = differences
+                .iter()
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .expect("Differences are never empty");
+
+            println!("{group1} and {group2}: lowest ΔE {difference:.1} with {vision} ok");
+        }
+    }
+
+    if failures == 0 {
+        println!("All {checked} pairs of distinct highlight groups can be told apart");
+    }
+
+    failures
+}
+
+/// Groups are matched case-insensitively like Neovim does.
+fn report_coverage(highlights: &HashMap<&str, &Highlight>) {
+    let defined: HashSet<String> = highlights
+        .keys()
+        .map(|group| group.to_lowercase())
+        .collect();
+
+    for category in &CATEGORIES {
+        let missing: Vec<&str> = category
+            .groups
+            .iter()
+            .copied()
+            .filter(|group| !defined.contains(&group.to_lowercase()))
+            .collect();
+
+        println!(
+            "{}: {} of {} groups defined",
+            category.name,
+            category.groups.len() - missing.len(),
+            category.groups.len()
+        );
+
+        if !missing.is_empty() {
+            println!("  missing: {}", missing.join(", "));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlight(group: &str, fg: Option<&str>, bg: Option<&str>) -> Highlight {
+        let color = |hex: &str| HighlightColor::Color(RgbColor::parse_from_hex(hex).unwrap());
+
+        let mut highlight = Highlight::new(group);
+        highlight.fg = fg.map(color);
+        highlight.bg = bg.map(color);
+        highlight
+    }
+
+    fn colors(fg: &str, bg: &str) -> Option<EffectiveColors> {
+        Some(EffectiveColors {
+            fg: RgbColor::parse_from_hex(fg).unwrap(),
+            bg: RgbColor::parse_from_hex(bg).unwrap(),
+        })
+    }
+
+    #[test]
+    fn test_effective_colors() {
+        let normal = highlight("Normal", Some("#c6c8d1"), Some("#161821"));
+        let comment = highlight("Comment", Some("#6b7089"), None);
+
+        let mut visual = highlight("Visual", None, Some("#272c42"));
+        visual.styles.insert(Style::Reverse, true);
+
+        let mut whitespace = Highlight::new("Whitespace");
+        whitespace.link = Some("Comment".to_string());
+
+        let mut cursor = highlight("Cursor", None, None);
+        cursor.fg = Some(HighlightColor::Background);
+        cursor.bg = Some(HighlightColor::Foreground);
+
+        let mut a = Highlight::new("A");
+        a.link = Some("B".to_string());
+        let mut b = Highlight::new("B");
+        b.link = Some("A".to_string());
+
+        let highlights: HashMap<&str, &Highlight> =
+            [&normal, &comment, &visual, &whitespace, &cursor, &a, &b]
+                .into_iter()
+                .map(|highlight| (highlight.group.as_str(), highlight))
+                .collect();
+
+        assert_eq!(
+            colors("#6b7089", "#161821"),
+            effective_colors("Comment", &highlights)
+        );
+        assert_eq!(
+            colors("#6b7089", "#161821"),
+            effective_colors("Whitespace", &highlights)
+        );
+        assert_eq!(
+            colors("#272c42", "#c6c8d1"),
+            effective_colors("Visual", &highlights)
+        );
+        assert_eq!(
+            colors("#161821", "#c6c8d1"),
+            effective_colors("Cursor", &highlights)
+        );
+        assert_eq!(None, effective_colors("A", &highlights));
+        assert_eq!(None, effective_colors("Missing", &highlights));
+
+        let highlights: HashMap<&str, &Highlight> = HashMap::from([("Comment", &comment)]);
+        assert_eq!(None, effective_colors("Comment", &highlights));
+    }
+
+    #[test]
+    fn test_check_vision() {
+        let args = CheckArgs {
+            filename: "theme.toml".to_string(),
+            min_contrast: 4.5,
+            min_apca: None,
+            ignore: Vec::new(),
+            cvd: true,
+            min_delta_e: 5.0,
+            coverage: false,
+            verbose: false,
+        };
+
+        let normal = highlight("Normal", Some("#c6c8d1"), Some("#161821"));
+
+        // Red and olive are far apart with normal vision but close to each other with deuteranopia
+        let red = RgbColor::parse_from_hex("#cc6666").unwrap();
+        let olive = RgbColor::parse_from_hex("#999966").unwrap();
+        let simulate = |color| Deficiency::Deuteranopia.simulate(color);
+
+        assert!(delta_e(&red, &olive) > args.min_delta_e);
+        assert!(delta_e(&simulate(&red), &simulate(&olive)) < args.min_delta_e);
+
+        let error = highlight("DiagnosticError", Some("#cc6666"), None);
+        let ok = highlight("DiagnosticOk", Some("#999966"), None);
+        let highlights = HashMap::from([
+            ("Normal", &normal),
+            ("DiagnosticError", &error),
+            ("DiagnosticOk", &ok),
+        ]);
+
+        assert_eq!(1, check_vision(&highlights, &args));
+
+        let ok = highlight("DiagnosticOk", Some("#84a0c6"), None);
+        let highlights = HashMap::from([
+            ("Normal", &normal),
+            ("DiagnosticError", &error),
+            ("DiagnosticOk", &ok),
+        ]);
+
+        assert_eq!(0, check_vision(&highlights, &args));
+    }
+}
@@ -1,15 +1,93 @@
 use regex::Regex;
 use std::fmt;
 
-use crate::error::{HslColorError, RgbColorError};
+use crate::error::{HslColorError, OklchColorError, RgbColorError};
 
 pub trait Color: fmt::Display {
     fn adjust(&self, saturation: f32, lightness: f32) -> Box<dyn Color>;
     fn lighten(&self, amount: f32) -> Box<dyn Color>;
     fn darken(&self, amount: f32) -> Box<dyn Color>;
+    fn scale(&self, saturation: f32, lightness: f32) -> Box<dyn Color>;
+    fn rotate(&self, degrees: f32) -> Box<dyn Color>;
+    fn saturate(&self, amount: f32) -> Box<dyn Color>;
+    fn desaturate(&self, amount: f32) -> Box<dyn Color>;
+    fn grayscale(&self) -> Box<dyn Color>;
+    fn invert(&self) -> Box<dyn Color>;
     fn hex(&self) -> String;
     fn copy(&self) -> Box<dyn Color>;
     fn to_rgb(&self) -> RgbColor;
+
+    fn alpha(&self) -> f32 {
+        1.0
+    }
+
+    fn with_alpha(&self, alpha: f32) -> Box<dyn Color> {
+        Box::new(AlphaColor {
+            color: self.copy(),
+            alpha,
+        })
+    }
+}
+
+pub(crate) fn parse_hex(hex: &str) -> Result<Box<dyn Color>, anyhow::Error> {
+    match hex.len() {
+        9 => Ok(Box::new(AlphaColor::parse_from_hex(hex)?)),
+        _ => Ok(Box::new(RgbColor::parse_from_hex(hex)?)),
+    }
+}
+
+fn parse_hex_channels<const N: usize>(hex: &str) -> Result<[u8; N], anyhow::Error> {
+    lazy_static! {
+        static ref RE: Regex =
+            Regex::new(r"^#((?:[a-fA-F\d]{2}){3,4})$").expect("Hex format regex is invalid");
+    }
+
+    fn extract(slice: &str) -> Result<u8, anyhow::Error> {
+        Ok(i64::from_str_radix(slice, 16)? as u8)
+    }
+
+    match RE.captures(hex) {
+        Some(capture) if capture[1].len() == N * 2 => {
+            let mut channels = [0; N];
+
+            for (i, channel) in channels.iter_mut().enumerate() {
+                *channel = extract(&capture[1][i * 2..i * 2 + 2])?;
+            }
+
+            Ok(channels)
+        }
+        _ => Err(RgbColorError::Format {
+            found: hex.to_string(),
+        }
+        .into()),
+    }
+}
+
+/// The alpha of the foreground is multiplied with the given alpha.
+pub(crate) fn blend(
+    foreground: &dyn Color,
+    background: &dyn Color,
+    alpha: f32,
+) -> Result<RgbColor, RgbColorError> {
+    validate_alpha(alpha)?;
+
+    mix(foreground, background, foreground.alpha() * alpha)
+}
+
+pub(crate) fn validate_alpha(alpha: f32) -> Result<(), RgbColorError> {
+    if !(0.0..=1.0).contains(&alpha) {
+        return Err(RgbColorError::Alpha { found: alpha });
+    }
+
+    Ok(())
+}
+
+fn validate_weight(weight: f32) -> Result<(), RgbColorError> {
+    if !(0.0..=1.0).contains(&weight) {
+        return Err(RgbColorError::Mix { found: weight });
+    }
+
+    Ok(())
 }
 
 pub(crate) fn mix(
@@ -17,9 +95,7 @@ pub(crate) fn mix(
     color2: &dyn Color,
     weight: f32,
 ) -> Result<RgbColor, RgbColorError> {
-    if !(0.0..=1.0).contains(&weight) {
-        return Err(RgbColorError::Mix { found: weight });
-    }
+    validate_weight(weight)?;
 
     let c1 = color1.to_rgb();
     let c2 = color2.to_rgb();
@@ -28,12 +104,90 @@ pub(crate) fn mix(
     let w2 = 1.0 - weight;
 
     Ok(RgbColor::new(
-        (c1.r as f32 * w1 + c2.r as f32 * w2) as u8,
-        (c1.g as f32 * w1 + c2.g as f32 * w2) as u8,
-        (c1.b as f32 * w1 + c2.b as f32 * w2) as u8,
+        (c1.r as f32 * w1 + c2.r as f32 * w2).round() as u8,
+        (c1.g as f32 * w1 + c2.g as f32 * w2).round() as u8,
+        (c1.b as f32 * w1 + c2.b as f32 * w2).round() as u8,
     ))
 }
 
+/// Mixing in linear light avoids the darkened blends of plain sRGB mixing.
+pub(crate) fn mix_linear(
+    color1: &dyn Color,
+    color2: &dyn Color,
+    weight: f32,
+) -> Result<RgbColor, RgbColorError> {
+    validate_weight(weight)?;
+
+    let c1 = color1.to_rgb().to_linear();
+    let c2 = color2.to_rgb().to_linear();
+
+    Ok(RgbColor::from_linear(lerp3(c1, c2, weight)))
+}
+
+pub(crate) fn mix_oklab(
+    color1: &dyn Color,
+    color2: &dyn Color,
+    weight: f32,
+) -> Result<OklchColor, RgbColorError> {
+    validate_weight(weight)?;
+
+    let c1 = linear_rgb_to_oklab(color1.to_rgb().to_linear());
+    let c2 = linear_rgb_to_oklab(color2.to_rgb().to_linear());
+
+    Ok(OklchColor::from_oklab_unchecked(lerp3(c1, c2, weight)))
+}
+
+/// The hue is interpolated along the shortest path around the color wheel.
+pub(crate) fn mix_hsl(
+    color1: &dyn Color,
+    color2: &dyn Color,
+    weight: f32,
+) -> Result<HslColor, RgbColorError> {
+    validate_weight(weight)?;
+
+    let c1 = color1.to_rgb().to_hsl_color();
+    let c2 = color2.to_rgb().to_hsl_color();
+
+    // The hue of an achromatic color carries no information, use the hue of the other color
+    let (h1, h2) = match (c1.saturation == 0.0, c2.saturation == 0.0) {
+        (true, false) => (c2.hue, c2.hue),
+        (false, true) => (c1.hue, c1.hue),
+        _ => (c1.hue, c2.hue),
+    };
+
+    let mut delta = h1 - h2;
+
+    if delta > 0.5 {
+        delta -= 1.0;
+    } else if delta < -0.5 {
+        delta += 1.0;
+    }
+
+    Ok(HslColor {
+        hue: (h2 + delta * weight).rem_euclid(1.0),
+        saturation: c1.saturation * weight + c2.saturation * (1.0 - weight),
+        lightness: c1.lightness * weight + c2.lightness * (1.0 - weight),
+    })
+}
+
+/// Moves a value towards `max` by a fraction of the remaining distance for positive factors and
+/// towards zero for negative factors.
+fn scale_value(value: f32, factor: f32, max: f32) -> f32 {
+    if factor > 0.0 {
+        value + (max - value) * factor
+    } else {
+        value + value * factor
+    }
+}
+
+fn lerp3(c1: [f32; 3], c2: [f32; 3], weight: f32) -> [f32; 3] {
+    [
+        c1[0] * weight + c2[0] * (1.0 - weight),
+        c1[1] * weight + c2[1] * (1.0 - weight),
+        c1[2] * weight + c2[2] * (1.0 - weight),
+    ]
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct HslColor {
     hue: f32,
@@ -54,6 +208,30 @@ impl Color for HslColor {
         Box::new(self.darken(amount))
     }
 
+    fn scale(&self, saturation: f32, lightness: f32) -> Box<dyn Color> {
+        Box::new(self.scale(saturation, lightness))
+    }
+
+    fn rotate(&self, degrees: f32) -> Box<dyn Color> {
+        Box::new(self.rotate(degrees))
+    }
+
+    fn saturate(&self, amount: f32) -> Box<dyn Color> {
+        Box::new(self.adjust(amount, 0.0))
+    }
+
+    fn desaturate(&self, amount: f32) -> Box<dyn Color> {
+        Box::new(self.adjust(-amount, 0.0))
+    }
+
+    fn grayscale(&self) -> Box<dyn Color> {
+        Box::new(self.grayscale())
+    }
+
+    fn invert(&self) -> Box<dyn Color> {
+        Box::new(self.to_rgb_color().invert())
+    }
+
     fn hex(&self) -> String {
         self.to_rgb_color().to_string()
     }
@@ -73,7 +251,7 @@ impl HslColor {
         saturation: f32,
         lightness: f32,
     ) -> Result<HslColor, HslColorError> {
-        if !(0.0..=360.0).contains(&hue) {
+        if !hue.is_finite() {
             Err(HslColorError::Hue { found: hue })?
         }
 
@@ -86,7 +264,7 @@ impl HslColor {
         }
 
         Ok(HslColor {
-            hue: hue / 360.0,
+            hue: hue.rem_euclid(360.0) / 360.0,
             saturation,
             lightness,
         })
@@ -116,6 +294,30 @@ impl HslColor {
         }
     }
 
+    pub(crate) fn scale(&self, saturation: f32, lightness: f32) -> HslColor {
+        HslColor {
+            hue: self.hue,
+            saturation: scale_value(self.saturation, saturation, 1.0).clamp(0.0, 1.0),
+            lightness: scale_value(self.lightness, lightness, 1.0).clamp(0.0, 1.0),
+        }
+    }
+
+    pub(crate) fn rotate(&self, degrees: f32) -> HslColor {
+        HslColor {
+            hue: (self.hue + degrees / 360.0).rem_euclid(1.0),
+            saturation: self.saturation,
+            lightness: self.lightness,
+        }
+    }
+
+    pub(crate) fn grayscale(&self) -> HslColor {
+        HslColor {
+            hue: self.hue,
+            saturation: 0.0,
+            lightness: self.lightness,
+        }
+    }
+
     fn to_rgb_color(self) -> RgbColor {
         let HslColor {
             hue: h,
@@ -194,7 +396,7 @@ impl fmt::Display for HslColor {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct RgbColor {
     r: u8,
     g: u8,
@@ -214,6 +416,30 @@ impl Color for RgbColor {
         Box::new(self.to_hsl_color().darken(amount))
     }
 
+    fn scale(&self, saturation: f32, lightness: f32) -> Box<dyn Color> {
+        Box::new(self.to_hsl_color().scale(saturation, lightness))
+    }
+
+    fn rotate(&self, degrees: f32) -> Box<dyn Color> {
+        Box::new(self.to_hsl_color().rotate(degrees))
+    }
+
+    fn saturate(&self, amount: f32) -> Box<dyn Color> {
+        Box::new(self.to_hsl_color().adjust(amount, 0.0))
+    }
+
+    fn desaturate(&self, amount: f32) -> Box<dyn Color> {
+        Box::new(self.to_hsl_color().adjust(-amount, 0.0))
+    }
+
+    fn grayscale(&self) -> Box<dyn Color> {
+        Box::new(self.to_hsl_color().grayscale())
+    }
+
+    fn invert(&self) -> Box<dyn Color> {
+        Box::new(self.invert())
+    }
+
     fn hex(&self) -> String {
         self.to_string()
     }
@@ -232,27 +458,49 @@ impl RgbColor {
         RgbColor { r, g, b }
     }
 
+    pub(crate) fn invert(&self) -> RgbColor {
+        RgbColor::new(255 - self.r, 255 - self.g, 255 - self.b)
+    }
+
     pub(crate) fn parse_from_hex(hex: &str) -> Result<RgbColor, anyhow::Error> {
-        lazy_static! {
-            static ref RE: Regex =
-                Regex::new(r"^#([a-fA-F\d]{6})$").expect("Hex format regex is invalid");
-        }
+        let [r, g, b] = parse_hex_channels(hex)?;
 
-        fn extract(slice: &str) -> Result<u8, anyhow::Error> {
-            Ok(i64::from_str_radix(slice, 16)? as u8)
-        }
+        Ok(RgbColor::new(r, g, b))
+    }
 
-        match RE.captures(hex) {
-            Some(capture) => Ok(RgbColor::new(
-                extract(&capture[1][0..2])?,
-                extract(&capture[1][2..4])?,
-                extract(&capture[1][4..6])?,
-            )),
-            None => Err(RgbColorError::Format {
-                found: hex.to_string(),
-            }
-            .into()),
+    pub(crate) fn from_linear([r, g, b]: [f32; 3]) -> RgbColor {
+        RgbColor::new(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b))
+    }
+
+    pub(crate) fn channels(self) -> [u8; 3] {
+        [self.r, self.g, self.b]
+    }
+
+    pub(crate) fn to_linear(self) -> [f32; 3] {
+        [
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+        ]
+    }
+
+    pub(crate) fn to_oklab(self) -> [f32; 3] {
+        linear_rgb_to_oklab(self.to_linear())
+    }
+
+    fn to_oklch_color(self) -> OklchColor {
+        let oklch = OklchColor::from_oklab_unchecked(linear_rgb_to_oklab(self.to_linear()));
+
+        // Achromatic color
+        if oklch.chroma < 0.0001 {
+            return OklchColor {
+                chroma: 0.0,
+                hue: 0.0,
+                ..oklch
+            };
         }
+
+        oklch
     }
 
     fn to_hsl_color(self) -> HslColor {
@@ -304,12 +552,365 @@ impl From<HslColor> for RgbColor {
     }
 }
 
+impl From<OklchColor> for RgbColor {
+    fn from(color: OklchColor) -> Self {
+        color.to_rgb_color()
+    }
+}
+
 impl fmt::Display for RgbColor {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
     }
 }
 
+/// Needs to be blended onto a background before it can be used in a highlight.
+pub struct AlphaColor {
+    color: Box<dyn Color>,
+    alpha: f32,
+}
+
+impl Color for AlphaColor {
+    fn adjust(&self, saturation: f32, lightness: f32) -> Box<dyn Color> {
+        self.color
+            .adjust(saturation, lightness)
+            .with_alpha(self.alpha)
+    }
+
+    fn lighten(&self, amount: f32) -> Box<dyn Color> {
+        self.color.lighten(amount).with_alpha(self.alpha)
+    }
+
+    fn darken(&self, amount: f32) -> Box<dyn Color> {
+        self.color.darken(amount).with_alpha(self.alpha)
+    }
+
+    fn scale(&self, saturation: f32, lightness: f32) -> Box<dyn Color> {
+        self.color
+            .scale(saturation, lightness)
+            .with_alpha(self.alpha)
+    }
+
+    fn rotate(&self, degrees: f32) -> Box<dyn Color> {
+        self.color.rotate(degrees).with_alpha(self.alpha)
+    }
+
+    fn saturate(&self, amount: f32) -> Box<dyn Color> {
+        self.color.saturate(amount).with_alpha(self.alpha)
+    }
+
+    fn desaturate(&self, amount: f32) -> Box<dyn Color> {
+        self.color.desaturate(amount).with_alpha(self.alpha)
+    }
+
+    fn grayscale(&self) -> Box<dyn Color> {
+        self.color.grayscale().with_alpha(self.alpha)
+    }
+
+    fn invert(&self) -> Box<dyn Color> {
+        self.color.invert().with_alpha(self.alpha)
+    }
+
+    fn hex(&self) -> String {
+        format!(
+            "{}{:02x}",
+            self.color.hex(),
+            (self.alpha * 255.0).round() as u8
+        )
+    }
+
+    fn copy(&self) -> Box<dyn Color> {
+        self.color.with_alpha(self.alpha)
+    }
+
+    fn to_rgb(&self) -> RgbColor {
+        self.color.to_rgb()
+    }
+
+    fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    fn with_alpha(&self, alpha: f32) -> Box<dyn Color> {
+        self.color.with_alpha(alpha)
+    }
+}
+
+impl AlphaColor {
+    pub(crate) fn parse_from_hex(hex: &str) -> Result<AlphaColor, anyhow::Error> {
+        let [r, g, b, a] = parse_hex_channels(hex)?;
+
+        Ok(AlphaColor {
+            color: Box::new(RgbColor::new(r, g, b)),
+            alpha: a as f32 / 255.0,
+        })
+    }
+}
+
+impl fmt::Display for AlphaColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}, Alpha: {}", self.color, self.alpha)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct OklchColor {
+    lightness: f32,
+    chroma: f32,
+    hue: f32,
+}
+
+impl Color for OklchColor {
+    fn adjust(&self, saturation: f32, lightness: f32) -> Box<dyn Color> {
+        Box::new(self.adjust(saturation, lightness))
+    }
+
+    fn lighten(&self, amount: f32) -> Box<dyn Color> {
+        Box::new(self.lighten(amount))
+    }
+
+    fn darken(&self, amount: f32) -> Box<dyn Color> {
+        Box::new(self.darken(amount))
+    }
+
+    fn scale(&self, saturation: f32, lightness: f32) -> Box<dyn Color> {
+        Box::new(self.scale(saturation, lightness))
+    }
+
+    fn rotate(&self, degrees: f32) -> Box<dyn Color> {
+        Box::new(self.rotate(degrees))
+    }
+
+    // Relative to the chroma range, so the amount means the same as for the other colors
+    fn saturate(&self, amount: f32) -> Box<dyn Color> {
+        Box::new(self.adjust(amount * OklchColor::MAX_CHROMA, 0.0))
+    }
+
+    fn desaturate(&self, amount: f32) -> Box<dyn Color> {
+        Box::new(self.adjust(-amount * OklchColor::MAX_CHROMA, 0.0))
+    }
+
+    fn grayscale(&self) -> Box<dyn Color> {
+        Box::new(self.grayscale())
+    }
+
+    fn invert(&self) -> Box<dyn Color> {
+        Box::new(self.to_rgb_color().invert())
+    }
+
+    fn hex(&self) -> String {
+        self.to_rgb_color().to_string()
+    }
+
+    fn copy(&self) -> Box<dyn Color> {
+        Box::new(*self)
+    }
+
+    fn to_rgb(&self) -> RgbColor {
+        self.to_rgb_color()
+    }
+}
+
+impl OklchColor {
+    // Roughly the highest chroma found within the sRGB gamut
+    const MAX_CHROMA: f32 = 0.37;
+
+    pub(crate) fn new(
+        lightness: f32,
+        chroma: f32,
+        hue: f32,
+    ) -> Result<OklchColor, OklchColorError> {
+        if !(0.0..=1.0).contains(&lightness) {
+            Err(OklchColorError::Lightness { found: lightness })?
+        }
+
+        if !chroma.is_finite() || chroma < 0.0 {
+            Err(OklchColorError::Chroma { found: chroma })?
+        }
+
+        if !hue.is_finite() {
+            Err(OklchColorError::Hue { found: hue })?
+        }
+
+        Ok(OklchColor {
+            lightness,
+            chroma,
+            hue: hue.rem_euclid(360.0),
+        })
+    }
+
+    pub(crate) fn from_oklab(
+        lightness: f32,
+        a: f32,
+        b: f32,
+    ) -> Result<OklchColor, OklchColorError> {
+        if !a.is_finite() || !b.is_finite() {
+            Err(OklchColorError::Chroma { found: a.hypot(b) })?
+        }
+
+        OklchColor::new(
+            lightness,
+            a.hypot(b),
+            b.atan2(a).to_degrees().rem_euclid(360.0),
+        )
+    }
+
+    fn from_oklab_unchecked([lightness, a, b]: [f32; 3]) -> OklchColor {
+        OklchColor {
+            lightness: lightness.clamp(0.0, 1.0),
+            chroma: a.hypot(b),
+            hue: b.atan2(a).to_degrees().rem_euclid(360.0),
+        }
+    }
+
+    /// The saturation delta is applied to the chroma directly.
+    pub(crate) fn adjust(&self, saturation: f32, lightness: f32) -> OklchColor {
+        OklchColor {
+            lightness: (self.lightness + lightness).clamp(0.0, 1.0),
+            chroma: (self.chroma + saturation).max(0.0),
+            hue: self.hue,
+        }
+    }
+
+    pub(crate) fn lighten(&self, amount: f32) -> OklchColor {
+        self.adjust(0.0, amount)
+    }
+
+    pub(crate) fn darken(&self, amount: f32) -> OklchColor {
+        self.adjust(0.0, -amount)
+    }
+
+    /// The chroma is scaled towards the highest chroma found within the sRGB gamut.
+    pub(crate) fn scale(&self, saturation: f32, lightness: f32) -> OklchColor {
+        OklchColor {
+            lightness: scale_value(self.lightness, lightness, 1.0).clamp(0.0, 1.0),
+            chroma: scale_value(self.chroma, saturation, Self::MAX_CHROMA.max(self.chroma))
+                .max(0.0),
+            hue: self.hue,
+        }
+    }
+
+    pub(crate) fn rotate(&self, degrees: f32) -> OklchColor {
+        OklchColor {
+            hue: (self.hue + degrees).rem_euclid(360.0),
+            ..*self
+        }
+    }
+
+    pub(crate) fn grayscale(&self) -> OklchColor {
+        OklchColor {
+            chroma: 0.0,
+            ..*self
+        }
+    }
+
+    fn to_oklab(self) -> [f32; 3] {
+        let hue = self.hue.to_radians();
+        [
+            self.lightness,
+            self.chroma * hue.cos(),
+            self.chroma * hue.sin(),
+        ]
+    }
+
+    /// Colors outside of the sRGB gamut are mapped back into it by reducing the chroma while
+    /// preserving lightness and hue.
+    fn to_rgb_color(self) -> RgbColor {
+        const EPSILON: f32 = 0.0001;
+
+        let in_gamut = |color: OklchColor| {
+            oklab_to_linear_rgb(color.to_oklab())
+                .iter()
+                .all(|c| (-EPSILON..=1.0 + EPSILON).contains(c))
+        };
+
+        let mut color = self;
+
+        if !in_gamut(color) {
+            let mut low = 0.0;
+            let mut high = self.chroma;
+
+            while high - low > EPSILON {
+                color.chroma = (low + high) / 2.0;
+
+                if in_gamut(color) {
+                    low = color.chroma;
+                } else {
+                    high = color.chroma;
+                }
+            }
+
+            color.chroma = low;
+        }
+
+        RgbColor::from_linear(oklab_to_linear_rgb(color.to_oklab()))
+    }
+}
+
+impl From<RgbColor> for OklchColor {
+    fn from(color: RgbColor) -> Self {
+        color.to_oklch_color()
+    }
+}
+
+impl fmt::Display for OklchColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Lightness: {}, Chroma: {}, Hue: {}",
+            self.lightness, self.chroma, self.hue
+        )
+    }
+}
+
+fn srgb_to_linear(channel: u8) -> f32 {
+    let c = channel as f32 / 255.0;
+
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+
+    let c = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+
+    (c * 255.0).round() as u8
+}
+
+#[allow(clippy::excessive_precision)]
+fn linear_rgb_to_oklab([r, g, b]: [f32; 3]) -> [f32; 3] {
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+#[allow(clippy::excessive_precision)]
+fn oklab_to_linear_rgb([lightness, a, b]: [f32; 3]) -> [f32; 3] {
+    let l = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+    [
+        4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+        -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+        -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -382,4 +983,185 @@ mod tests {
         assert!((hsl.saturation - 0.5).abs() < 0.01);
         assert!((hsl.lightness - 0.5).abs() < 0.01);
     }
+
+    #[test]
+    fn test_oklch_color_new() {
+        assert!(OklchColor::new(0.7, 0.1, 120.0).is_ok());
+        assert_eq!(
+            OklchColor::new(1.2, 0.1, 120.0).unwrap_err(),
+            OklchColorError::Lightness { found: 1.2 }
+        );
+        assert_eq!(
+            OklchColor::new(0.7, -0.1, 120.0).unwrap_err(),
+            OklchColorError::Chroma { found: -0.1 }
+        );
+    }
+
+    #[test]
+    fn test_oklch_to_rgb() {
+        assert_eq!(
+            "#ffffff",
+            OklchColor::new(1.0, 0.0, 0.0).unwrap().to_rgb().hex()
+        );
+        assert_eq!(
+            "#000000",
+            OklchColor::new(0.0, 0.0, 0.0).unwrap().to_rgb().hex()
+        );
+        assert_eq!(
+            "#ff0000",
+            OklchColor::new(0.628, 0.2577, 29.23)
+                .unwrap()
+                .to_rgb()
+                .hex()
+        );
+        assert_eq!(
+            "#ff0000",
+            OklchColor::from_oklab(0.628, 0.2249, 0.1258)
+                .unwrap()
+                .to_rgb()
+                .hex()
+        );
+    }
+
+    #[test]
+    fn test_oklch_gamut_mapping() {
+        let rgb = OklchColor::new(0.7, 0.5, 140.0).unwrap().to_rgb();
+        let oklch = OklchColor::from(rgb);
+
+        assert!((oklch.lightness - 0.7).abs() < 0.01);
+        assert!((oklch.hue - 140.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_rgb_to_oklch() {
+        let rgb = RgbColor::parse_from_hex("#40bf40").unwrap();
+        let oklch = OklchColor::from(rgb);
+
+        assert_eq!(rgb.hex(), oklch.hex());
+    }
+
+    #[test]
+    fn test_mix() {
+        let black = RgbColor::parse_from_hex("#000000").unwrap();
+        let white = RgbColor::parse_from_hex("#ffffff").unwrap();
+
+        assert_eq!("#808080", mix(&black, &white, 0.5).unwrap().hex());
+        assert_eq!("#000000", mix(&black, &white, 1.0).unwrap().hex());
+        assert_eq!(
+            RgbColorError::Mix { found: 1.5 },
+            mix(&black, &white, 1.5).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_mix_linear() {
+        let red = RgbColor::parse_from_hex("#ff0000").unwrap();
+        let green = RgbColor::parse_from_hex("#00ff00").unwrap();
+
+        assert_eq!("#bcbc00", mix_linear(&red, &green, 0.5).unwrap().hex());
+    }
+
+    #[test]
+    fn test_mix_oklab() {
+        let black = RgbColor::parse_from_hex("#000000").unwrap();
+        let white = RgbColor::parse_from_hex("#ffffff").unwrap();
+
+        assert_eq!("#636363", mix_oklab(&black, &white, 0.5).unwrap().hex());
+        assert_eq!("#ffffff", mix_oklab(&black, &white, 0.0).unwrap().hex());
+    }
+
+    #[test]
+    fn test_mix_hsl_shortest_hue() {
+        let c1 = HslColor::new(350.0, 1.0, 0.5).unwrap();
+        let c2 = HslColor::new(30.0, 1.0, 0.5).unwrap();
+        let mixed = mix_hsl(&c1, &c2, 0.5).unwrap();
+
+        assert!((mixed.hue * 360.0 - 10.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_hsl_color_hue_wraps() {
+        let hsl = HslColor::new(-90.0, 0.5, 0.5).unwrap();
+        assert_eq!(hsl.hue, 270.0 / 360.0);
+
+        let hsl = HslColor::new(410.0, 0.5, 0.5).unwrap();
+        assert!((hsl.hue - 50.0 / 360.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_hsl_color_rotate() {
+        let hsl = HslColor::new(300.0, 0.5, 0.5).unwrap();
+        assert!((hsl.rotate(90.0).hue - 30.0 / 360.0).abs() < 0.0001);
+        assert!((hsl.rotate(-330.0).hue - 330.0 / 360.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_grayscale() {
+        let rgb = RgbColor::parse_from_hex("#40bf40").unwrap();
+        assert_eq!("#808080", rgb.grayscale().hex());
+    }
+
+    #[test]
+    fn test_invert() {
+        let rgb = RgbColor::parse_from_hex("#40bf40").unwrap();
+        assert_eq!("#bf40bf", rgb.invert().hex());
+    }
+
+    #[test]
+    fn test_hsl_color_scale() {
+        let hsl = HslColor::new(120.0, 0.5, 0.8).unwrap();
+
+        let lightened = hsl.scale(0.0, 0.5);
+        assert!((lightened.lightness - 0.9).abs() < 0.0001);
+
+        let darkened = hsl.scale(-0.5, -0.5);
+        assert!((darkened.saturation - 0.25).abs() < 0.0001);
+        assert!((darkened.lightness - 0.4).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_oklch_color_saturate() {
+        let oklch = OklchColor::new(0.7, 0.1, 120.0).unwrap();
+
+        assert_eq!(
+            OklchColor::new(0.7, 0.137, 120.0).unwrap().hex(),
+            Color::saturate(&oklch, 0.1).hex()
+        );
+        assert_eq!(
+            OklchColor::new(0.7, 0.063, 120.0).unwrap().hex(),
+            Color::desaturate(&oklch, 0.1).hex()
+        );
+    }
+
+    #[test]
+    fn test_parse_hex_with_alpha() {
+        let color = parse_hex("#ff000080").unwrap();
+        assert_eq!("#ff000080", color.hex());
+        assert_eq!("#ff0000", color.to_rgb().hex());
+        assert!((color.alpha() - 128.0 / 255.0).abs() < 0.0001);
+
+        assert_eq!(1.0, parse_hex("#ff0000").unwrap().alpha());
+        assert!(RgbColor::parse_from_hex("#ff000080").is_err());
+        assert!(parse_hex("#ff00008").is_err());
+    }
+
+    #[test]
+    fn test_alpha_preserved() {
+        let color = parse_hex("#40bf4080").unwrap().lighten(0.1);
+        assert!((color.alpha() - 128.0 / 255.0).abs() < 0.0001);
+        assert_eq!(1.0, color.with_alpha(1.0).alpha());
+    }
+
+    #[test]
+    fn test_blend() {
+        let fg = parse_hex("#ffffff80").unwrap();
+        let bg = RgbColor::parse_from_hex("#000000").unwrap();
+
+        assert_eq!("#808080", blend(fg.as_ref(), &bg, 1.0).unwrap().hex());
+        assert_eq!("#404040", blend(fg.as_ref(), &bg, 0.5).unwrap().hex());
+        assert_eq!(
+            RgbColorError::Alpha { found: 2.0 },
+            blend(fg.as_ref(), &bg, 2.0).unwrap_err()
+        );
+    }
 }
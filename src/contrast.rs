@@ -0,0 +1,207 @@
+use std::ops::RangeInclusive;
+
+use crate::color::{Color, RgbColor};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Metric {
+    Wcag,
+    Apca,
+}
+
+impl Metric {
+    fn score(self, foreground: &dyn Color, background: &dyn Color) -> f32 {
+        match self {
+            Metric::Wcag => wcag_ratio(foreground, background),
+            Metric::Apca => apca_contrast(foreground, background).abs(),
+        }
+    }
+
+    pub(crate) fn range(self) -> RangeInclusive<f32> {
+        match self {
+            Metric::Wcag => 1.0..=21.0,
+            Metric::Apca => 0.0..=108.0,
+        }
+    }
+}
+
+pub(crate) fn relative_luminance(color: RgbColor) -> f32 {
+    let [r, g, b] = color.to_linear();
+
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+pub(crate) fn wcag_ratio(color1: &dyn Color, color2: &dyn Color) -> f32 {
+    let l1 = relative_luminance(color1.to_rgb());
+    let l2 = relative_luminance(color2.to_rgb());
+
+    (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05)
+}
+
+/// APCA-W3 (0.0.98G-4g). Positive values are dark text on a light background, negative values
+/// light text on a dark background.
+pub(crate) fn apca_contrast(text: &dyn Color, background: &dyn Color) -> f32 {
+    const BLACK_THRESHOLD: f32 = 0.022;
+    const BLACK_CLAMP: f32 = 1.414;
+    const SCALE: f32 = 1.14;
+    const OFFSET: f32 = 0.027;
+    const LOW_CLIP: f32 = 0.1;
+    const DELTA_Y_MIN: f32 = 0.0005;
+
+    fn luminance(color: RgbColor) -> f32 {
+        let [r, g, b] = color.channels().map(|c| (c as f32 / 255.0).powf(2.4));
+        let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+
+        // Soft clamp of near black colors
+        match y > BLACK_THRESHOLD {
+            true => y,
+            false => y + (BLACK_THRESHOLD - y).powf(BLACK_CLAMP),
+        }
+    }
+
+    let y_text = luminance(text.to_rgb());
+    let y_background = luminance(background.to_rgb());
+
+    if (y_background - y_text).abs() < DELTA_Y_MIN {
+        return 0.0;
+    }
+
+    let contrast = if y_background > y_text {
+        let sapc = (y_background.powf(0.56) - y_text.powf(0.57)) * SCALE;
+
+        match sapc < LOW_CLIP {
+            true => 0.0,
+            false => sapc - OFFSET,
+        }
+    } else {
+        let sapc = (y_background.powf(0.65) - y_text.powf(0.62)) * SCALE;
+
+        match sapc > -LOW_CLIP {
+            true => 0.0,
+            false => sapc + OFFSET,
+        }
+    };
+
+    contrast * 100.0
+}
+
+/// Returns `None` if neither black nor white would satisfy the target.
+pub(crate) fn ensure_contrast(
+    color: &dyn Color,
+    against: &dyn Color,
+    target: f32,
+    metric: Metric,
+) -> Option<Box<dyn Color>> {
+    const STEPS: usize = 24;
+
+    if metric.score(color, against) >= target {
+        return Some(color.copy());
+    }
+
+    let candidates = [
+        |color: &dyn Color, amount: f32| color.lighten(amount),
+        |color: &dyn Color, amount: f32| color.darken(amount),
+    ]
+    .into_iter()
+    .filter_map(|walk| {
+        if metric.score(walk(color, 1.0).as_ref(), against) < target {
+            return None;
+        }
+
+        // Binary search the smallest lightness delta that satisfies the ratio
+        let mut low = 0.0;
+        let mut high = 1.0;
+
+        for _ in 0..STEPS {
+            let amount = (low + high) / 2.0;
+
+            if metric.score(walk(color, amount).as_ref(), against) >= target {
+                high = amount;
+            } else {
+                low = amount;
+            }
+        }
+
+        Some((high, walk(color, high)))
+    });
+
+    candidates
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, color)| color)
+}
+
+pub(crate) fn readable<'a>(background: &dyn Color, candidates: &[&'a dyn Color]) -> &'a dyn Color {
+    candidates
+        .iter()
+        .copied()
+        .max_by(|a, b| wcag_ratio(*a, background).total_cmp(&wcag_ratio(*b, background)))
+        .expect("At least one candidate is required")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::HslColor;
+
+    #[test]
+    fn test_wcag_ratio() {
+        let black = RgbColor::parse_from_hex("#000000").unwrap();
+        let white = RgbColor::parse_from_hex("#ffffff").unwrap();
+        let gray = RgbColor::parse_from_hex("#777777").unwrap();
+
+        assert!((wcag_ratio(&black, &white) - 21.0).abs() < 0.01);
+        assert!((wcag_ratio(&white, &black) - 21.0).abs() < 0.01);
+        assert!((wcag_ratio(&gray, &white) - 4.48).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_ensure_contrast() {
+        let background = HslColor::new(230.0, 0.2, 0.11).unwrap();
+        let comment = HslColor::new(230.0, 0.1, 0.3).unwrap();
+
+        let adjusted = ensure_contrast(&comment, &background, 4.5, Metric::Wcag).unwrap();
+        let ratio = wcag_ratio(adjusted.as_ref(), &background);
+
+        assert!(ratio >= 4.5);
+        assert!(ratio < 4.7);
+    }
+
+    #[test]
+    fn test_apca_contrast() {
+        let black = RgbColor::parse_from_hex("#000000").unwrap();
+        let white = RgbColor::parse_from_hex("#ffffff").unwrap();
+        let gray = RgbColor::parse_from_hex("#888888").unwrap();
+
+        assert!((apca_contrast(&black, &white) - 106.04).abs() < 0.1);
+        assert!((apca_contrast(&white, &black) + 107.88).abs() < 0.1);
+        assert!((apca_contrast(&gray, &white) - 63.06).abs() < 0.1);
+        assert_eq!(0.0, apca_contrast(&gray, &gray));
+    }
+
+    #[test]
+    fn test_ensure_contrast_apca() {
+        let background = HslColor::new(230.0, 0.2, 0.11).unwrap();
+        let comment = HslColor::new(230.0, 0.1, 0.3).unwrap();
+
+        let adjusted = ensure_contrast(&comment, &background, 60.0, Metric::Apca).unwrap();
+        let contrast = apca_contrast(adjusted.as_ref(), &background).abs();
+
+        assert!(contrast >= 60.0);
+        assert!(contrast < 61.0);
+    }
+
+    #[test]
+    fn test_ensure_contrast_unreachable() {
+        let gray = RgbColor::parse_from_hex("#777777").unwrap();
+
+        assert!(ensure_contrast(&gray, &gray, 21.0, Metric::Wcag).is_none());
+    }
+
+    #[test]
+    fn test_readable() {
+        let background = RgbColor::parse_from_hex("#161822").unwrap();
+        let dark = RgbColor::parse_from_hex("#33374c").unwrap();
+        let light = RgbColor::parse_from_hex("#c6c8d1").unwrap();
+
+        assert_eq!("#c6c8d1", readable(&background, &[&dark, &light]).hex());
+    }
+}
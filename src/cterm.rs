@@ -0,0 +1,99 @@
+use crate::{
+    color::RgbColor,
+    highlight::{CtermColor, Highlight, HighlightColor},
+};
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+lazy_static! {
+    // The first 16 entries are left out since every terminal defines them differently
+    static ref PALETTE: Vec<(u8, [f32; 3])> = (16..=255)
+        .map(|index| (index, xterm_color(index).to_oklab()))
+        .collect();
+}
+
+fn xterm_color(index: u8) -> RgbColor {
+    match index {
+        16..=231 => {
+            let index = (index - 16) as usize;
+
+            RgbColor::new(
+                CUBE_LEVELS[index / 36],
+                CUBE_LEVELS[index / 6 % 6],
+                CUBE_LEVELS[index % 6],
+            )
+        }
+        _ => {
+            let level = 8 + 10 * (index.saturating_sub(232));
+            RgbColor::new(level, level, level)
+        }
+    }
+}
+
+pub(crate) fn nearest_index(color: RgbColor) -> u8 {
+    let [l, a, b] = color.to_oklab();
+
+    PALETTE
+        .iter()
+        .min_by(|(_, c1), (_, c2)| {
+            let distance =
+                |[l2, a2, b2]: [f32; 3]| (l - l2).powi(2) + (a - a2).powi(2) + (b - b2).powi(2);
+            distance(*c1).total_cmp(&distance(*c2))
+        })
+        .map(|(index, _)| *index)
+        .expect("Palette is never empty")
+}
+
+/// Terminal attributes that are set explicitly are kept.
+pub(crate) fn apply_fallback(highlight: &mut Highlight) {
+    // Neovim doesn't accept the special fg and bg values as terminal color names
+    let fallback = |color: Option<HighlightColor>| match color? {
+        HighlightColor::Color(color) => Some(CtermColor::Index(nearest_index(color))),
+        HighlightColor::Foreground | HighlightColor::Background | HighlightColor::None => None,
+    };
+
+    if highlight.ctermfg.is_none() {
+        highlight.ctermfg = fallback(highlight.fg);
+    }
+
+    if highlight.ctermbg.is_none() {
+        highlight.ctermbg = fallback(highlight.bg);
+    }
+
+    if highlight.cterm.is_none() && !highlight.styles.is_empty() {
+        highlight.cterm = Some(highlight.styles.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xterm_color() {
+        assert_eq!(RgbColor::new(0, 0, 0), xterm_color(16));
+        assert_eq!(RgbColor::new(255, 135, 0), xterm_color(208));
+        assert_eq!(RgbColor::new(255, 255, 255), xterm_color(231));
+        assert_eq!(RgbColor::new(8, 8, 8), xterm_color(232));
+        assert_eq!(RgbColor::new(238, 238, 238), xterm_color(255));
+    }
+
+    #[test]
+    fn test_nearest_index() {
+        assert_eq!(208, nearest_index(RgbColor::new(255, 135, 0)));
+        assert_eq!(235, nearest_index(RgbColor::new(0x26, 0x26, 0x26)));
+        assert_eq!(167, nearest_index(RgbColor::new(0xd7, 0x5f, 0x5f)));
+    }
+
+    #[test]
+    fn test_apply_fallback() {
+        let mut highlight = Highlight::new("TabLineSel");
+        highlight.fg = Some(HighlightColor::Foreground);
+        highlight.bg = Some(HighlightColor::Color(RgbColor::new(255, 135, 0)));
+
+        apply_fallback(&mut highlight);
+
+        assert_eq!(None, highlight.ctermfg);
+        assert_eq!(Some(CtermColor::Index(208)), highlight.ctermbg);
+    }
+}
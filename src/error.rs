@@ -1,6 +1,6 @@
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum HslColorError {
-    #[error("Invalid hue value (expected 0-360, got {found:?})")]
+    #[error("Invalid hue value (expected a finite number, got {found:?})")]
     Hue { found: f32 },
     #[error("Invalid saturation value (expected 0-1, got {found:?})")]
     Saturation { found: f32 },
@@ -8,12 +8,24 @@ pub enum HslColorError {
     Lightness { found: f32 },
 }
 
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum OklchColorError {
+    #[error("Invalid lightness value (expected 0-1, got {found:?})")]
+    Lightness { found: f32 },
+    #[error("Invalid chroma value (expected a positive number, got {found:?})")]
+    Chroma { found: f32 },
+    #[error("Invalid hue value (expected a finite number, got {found:?})")]
+    Hue { found: f32 },
+}
+
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum RgbColorError {
     #[error("Invalid hex format {found:?}")]
     Format { found: String },
     #[error("Invalid mix value (expected 0-1) got {found:?}")]
     Mix { found: f32 },
+    #[error("Invalid alpha value (expected 0-1) got {found:?}")]
+    Alpha { found: f32 },
 }
 
 #[derive(thiserror::Error, Debug, PartialEq)]
@@ -22,22 +34,110 @@ pub enum ThemeError {
     InvalidBackground { background: String },
     #[error("Missing toml value")]
     MissingValue,
-    #[error("Invalid color format {color:?}")]
-    InvalidColor { color: String },
     #[error("Referenced color {color:?} is not present in palette")]
     MissingColor { color: String },
-    #[error("Referenced hue {hue:?} is not present in palette")]
-    MissingHue { hue: String },
-    #[error("Can't lookup hue {hue:?} due to toml section missing")]
-    MissingHueSection { hue: String },
+    #[error("Color name {color:?} is reserved for function options")]
+    ReservedColor { color: String },
+    #[error("Invalid value {expression:?} for {key:?}")]
+    InvalidEntry { key: String, expression: String },
+    #[error("Colors reference each other in a cycle: {cycle}")]
+    ColorCycle { cycle: String },
+    #[error("Referenced variable {variable:?} is not present in the hues or vars sections")]
+    MissingVariable { variable: String },
+    #[error("Variable {variable:?} is declared in both the hues and vars sections")]
+    DuplicateVariable { variable: String },
+    #[error("Unexpected {token:?} at column {column} in {expression:?}")]
+    UnexpectedToken {
+        token: String,
+        column: usize,
+        expression: String,
+    },
+    #[error("Unexpected end of expression {expression:?}")]
+    UnexpectedEnd { expression: String },
+    #[error("Unknown function {function:?} at column {column}")]
+    UnknownFunction { function: String, column: usize },
+    #[error("Function {function:?} at column {column} expects {expected} arguments, got {found}")]
+    ArgumentCount {
+        function: String,
+        column: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Expected a number at column {column}, got {found:?}")]
+    ExpectedNumber { found: String, column: usize },
+    #[error("Division by zero in expression at column {column}")]
+    DivisionByZero { column: usize },
+    #[error("Invalid scale factor at column {column} (expected -1 to 1, got {found:?})")]
+    InvalidScale { found: f32, column: usize },
+    #[error("Invalid contrast at column {column} (expected {min}-{max}, got {found:?})")]
+    InvalidContrast {
+        found: f32,
+        column: usize,
+        min: f32,
+        max: f32,
+    },
+    #[error("Contrast {ratio:?} at column {column} can't be reached by changing the lightness")]
+    UnreachableContrast { ratio: f32, column: usize },
+    #[error("Unknown option {option:?} at column {column}")]
+    UnknownOption { option: String, column: usize },
+    #[error("Expected a color at column {column}, got {found:?}")]
+    ExpectedColor { found: String, column: usize },
+    #[error("Color {color:?} is translucent, flatten it onto a background with blend() first")]
+    TranslucentColor { color: String },
     #[error("Invalid highlight {highlight:?}")]
     InvalidHighlight { highlight: String },
     #[error("Unknown style option {option:?}")]
     UnknownStyleOption { option: String },
+    #[error("Unknown attribute {attribute:?} in highlight {group:?}")]
+    UnknownAttribute { attribute: String, group: String },
+    #[error("Attribute {attribute:?} in highlight {group:?} must be {expected}")]
+    InvalidAttribute {
+        attribute: String,
+        group: String,
+        expected: String,
+    },
+    #[error("Highlight {group:?} can't combine a link with other attributes")]
+    LinkWithAttributes { group: String },
+    #[error("Highlight {group:?} links to {target:?} which is neither defined nor a built-in group{suggestion}")]
+    UnknownLinkTarget {
+        group: String,
+        target: String,
+        suggestion: String,
+    },
+    #[error("Highlights link to each other in a cycle: {cycle}")]
+    LinkCycle { cycle: String },
+    #[error("Highlight {group:?} derives from {base:?} which is not defined in the theme")]
+    UnknownBase { group: String, base: String },
+    #[error("Highlights derive from each other in a cycle: {cycle}")]
+    DerivationCycle { cycle: String },
+    #[error("Unknown role {role:?}")]
+    UnknownRole { role: String },
+    #[error("Unknown preset {preset:?}")]
+    UnknownPreset { preset: String },
+    #[error("Pattern {pattern:?} doesn't match any standard Neovim group")]
+    EmptyPattern { pattern: String },
+    #[error("Bulk entry {name:?} must have a list of groups and a value")]
+    InvalidBulk { name: String },
+    #[error("Global {global:?} must be a color, boolean, number, string or list")]
+    InvalidGlobal { global: String },
 }
 
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum FileError {
     #[error("File {path:?} not found")]
     FileNotFound { path: String },
+    #[error("The extends key in {path:?} must be the path of a theme file")]
+    InvalidExtends { path: String },
+    #[error("The include key in {path:?} must be a list of file paths")]
+    InvalidInclude { path: String },
+    #[error("Included file {path:?} can only contain highlights, bulk and globals, found {key:?}")]
+    InvalidFragment { path: String, key: String },
+    #[error("Theme files extend or include each other in a cycle: {cycle}")]
+    FileCycle { cycle: String },
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum CheckError {
+    #[error("Found {contrast} contrast and {vision} color vision issues")]
+    Issues { contrast: usize, vision: usize },
 }
@@ -0,0 +1,542 @@
+use std::{fmt, iter::Peekable, str::CharIndices};
+
+use crate::error::ThemeError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    Identifier(String),
+    Variable(String),
+    Number(f32),
+    Hex(String),
+    Operator(Operator),
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Identifier(name) => write!(f, "{name}"),
+            Token::Variable(name) => write!(f, "${name}"),
+            Token::Number(number) => write!(f, "{number}"),
+            Token::Hex(hex) => write!(f, "{hex}"),
+            Token::Operator(operator) => write!(f, "{operator}"),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operator {
+    fn precedence(self) -> u8 {
+        match self {
+            Operator::Add | Operator::Subtract => 1,
+            Operator::Multiply | Operator::Divide => 2,
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operator::Add => write!(f, "+"),
+            Operator::Subtract => write!(f, "-"),
+            Operator::Multiply => write!(f, "*"),
+            Operator::Divide => write!(f, "/"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Spanned {
+    token: Token,
+    column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ExpressionKind {
+    Number(f32),
+    Hex(String),
+    Variable(String),
+    Reference(String),
+    Call {
+        function: String,
+        arguments: Vec<Expression>,
+    },
+    Negate(Box<Expression>),
+    Binary {
+        operator: Operator,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Expression {
+    pub kind: ExpressionKind,
+    pub column: usize,
+}
+
+impl Expression {
+    pub(crate) fn references(&self) -> Vec<&str> {
+        match &self.kind {
+            ExpressionKind::Reference(name) => vec![name.as_str()],
+            ExpressionKind::Call { arguments, .. } => arguments
+                .iter()
+                .flat_map(|argument| argument.references())
+                .collect(),
+            ExpressionKind::Negate(operand) => operand.references(),
+            ExpressionKind::Binary { left, right, .. } => {
+                let mut references = left.references();
+                references.extend(right.references());
+                references
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Nested arithmetic is always parenthesized to keep the grouping unambiguous
+        fn operand(f: &mut fmt::Formatter<'_>, expression: &Expression) -> fmt::Result {
+            match expression.kind {
+                ExpressionKind::Binary { .. } => write!(f, "({expression})"),
+                _ => write!(f, "{expression}"),
+            }
+        }
+
+        match &self.kind {
+            ExpressionKind::Number(number) => write!(f, "{number}"),
+            ExpressionKind::Hex(hex) => write!(f, "{hex}"),
+            ExpressionKind::Variable(name) => write!(f, "${name}"),
+            ExpressionKind::Reference(name) => write!(f, "{name}"),
+            ExpressionKind::Call {
+                function,
+                arguments,
+            } => {
+                write!(f, "{function}(")?;
+
+                for (i, argument) in arguments.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{argument}")?;
+                }
+
+                write!(f, ")")
+            }
+            ExpressionKind::Negate(expression) => {
+                write!(f, "-")?;
+                operand(f, expression)
+            }
+            ExpressionKind::Binary {
+                operator,
+                left,
+                right,
+            } => {
+                operand(f, left)?;
+                write!(f, " {operator} ")?;
+                operand(f, right)
+            }
+        }
+    }
+}
+
+pub(crate) fn parse_expression(input: &str) -> Result<Expression, ThemeError> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser {
+        input,
+        tokens: &tokens,
+        position: 0,
+    };
+
+    let expression = parser.expression()?;
+
+    match parser.next() {
+        Some(spanned) => Err(parser.unexpected(spanned)),
+        None => Ok(expression),
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+fn take_while(
+    input: &str,
+    chars: &mut Peekable<CharIndices>,
+    start: usize,
+    predicate: fn(char) -> bool,
+) -> String {
+    let mut end = start;
+
+    while let Some(&(i, c)) = chars.peek() {
+        if !predicate(c) {
+            break;
+        }
+
+        end = i + c.len_utf8();
+        chars.next();
+    }
+
+    input[start..end].to_string()
+}
+
+fn tokenize(input: &str) -> Result<Vec<Spanned>, ThemeError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        let column = input[..start].chars().count() + 1;
+
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' => {
+                chars.next();
+                Token::LeftParen
+            }
+            ')' => {
+                chars.next();
+                Token::RightParen
+            }
+            ',' => {
+                chars.next();
+                Token::Comma
+            }
+            '+' | '-' | '*' | '/' => {
+                chars.next();
+                Token::Operator(match c {
+                    '+' => Operator::Add,
+                    '-' => Operator::Subtract,
+                    '*' => Operator::Multiply,
+                    _ => Operator::Divide,
+                })
+            }
+            '#' => {
+                chars.next();
+                let digits =
+                    take_while(input, &mut chars, start + 1, |c| c.is_ascii_alphanumeric());
+                Token::Hex(format!("#{digits}"))
+            }
+            '$' => {
+                chars.next();
+                let name = take_while(input, &mut chars, start + 1, is_identifier_char);
+
+                if name.is_empty() {
+                    return Err(ThemeError::UnexpectedToken {
+                        token: "$".to_string(),
+                        column,
+                        expression: input.to_string(),
+                    });
+                }
+
+                Token::Variable(name)
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let number =
+                    take_while(input, &mut chars, start, |c| c.is_ascii_digit() || c == '.');
+
+                match number.parse::<f32>() {
+                    Ok(number) => Token::Number(number),
+                    Err(_) => {
+                        return Err(ThemeError::UnexpectedToken {
+                            token: number,
+                            column,
+                            expression: input.to_string(),
+                        })
+                    }
+                }
+            }
+            c if is_identifier_start(c) => {
+                Token::Identifier(take_while(input, &mut chars, start, is_identifier_char))
+            }
+            unknown => {
+                return Err(ThemeError::UnexpectedToken {
+                    token: unknown.to_string(),
+                    column,
+                    expression: input.to_string(),
+                })
+            }
+        };
+
+        tokens.push(Spanned { token, column });
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: &'a [Spanned],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Option<&'a Spanned> {
+        let spanned = self.tokens.get(self.position);
+        self.position += 1;
+        spanned
+    }
+
+    fn peek(&self) -> Option<&'a Spanned> {
+        self.tokens.get(self.position)
+    }
+
+    fn unexpected(&self, spanned: &Spanned) -> ThemeError {
+        ThemeError::UnexpectedToken {
+            token: spanned.token.to_string(),
+            column: spanned.column,
+            expression: self.input.to_string(),
+        }
+    }
+
+    fn unexpected_end(&self) -> ThemeError {
+        ThemeError::UnexpectedEnd {
+            expression: self.input.to_string(),
+        }
+    }
+
+    /// Parses a binary expression with operators binding at least as tight as `min_precedence`.
+    fn binary(&mut self, min_precedence: u8) -> Result<Expression, ThemeError> {
+        let mut left = self.unary()?;
+
+        while let Some(Spanned {
+            token: Token::Operator(operator),
+            column,
+        }) = self.peek()
+        {
+            if operator.precedence() < min_precedence {
+                break;
+            }
+
+            self.next();
+            let right = self.binary(operator.precedence() + 1)?;
+
+            left = Expression {
+                column: *column,
+                kind: ExpressionKind::Binary {
+                    operator: *operator,
+                    left: Box::new(left),
+                    right: Box::new(right),
+                },
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn expression(&mut self) -> Result<Expression, ThemeError> {
+        self.binary(0)
+    }
+
+    fn unary(&mut self) -> Result<Expression, ThemeError> {
+        let spanned = self.next().ok_or_else(|| self.unexpected_end())?;
+        let column = spanned.column;
+
+        let kind = match &spanned.token {
+            Token::Operator(Operator::Subtract) => ExpressionKind::Negate(Box::new(self.unary()?)),
+            Token::LeftParen => {
+                let expression = self.expression()?;
+                let spanned = self.next().ok_or_else(|| self.unexpected_end())?;
+
+                return match spanned.token {
+                    Token::RightParen => Ok(expression),
+                    _ => Err(self.unexpected(spanned)),
+                };
+            }
+            Token::Number(number) => ExpressionKind::Number(*number),
+            Token::Hex(hex) => ExpressionKind::Hex(hex.clone()),
+            Token::Variable(name) => ExpressionKind::Variable(name.clone()),
+            Token::Identifier(name) => match self.peek() {
+                Some(Spanned {
+                    token: Token::LeftParen,
+                    ..
+                }) => {
+                    self.next();
+                    ExpressionKind::Call {
+                        function: name.clone(),
+                        arguments: self.arguments()?,
+                    }
+                }
+                _ => ExpressionKind::Reference(name.clone()),
+            },
+            _ => return Err(self.unexpected(spanned)),
+        };
+
+        Ok(Expression { kind, column })
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Expression>, ThemeError> {
+        let mut arguments = Vec::new();
+
+        if let Some(Spanned {
+            token: Token::RightParen,
+            ..
+        }) = self.peek()
+        {
+            self.next();
+            return Ok(arguments);
+        }
+
+        loop {
+            arguments.push(self.expression()?);
+
+            let spanned = self.next().ok_or_else(|| self.unexpected_end())?;
+
+            match spanned.token {
+                Token::Comma => continue,
+                Token::RightParen => return Ok(arguments),
+                _ => return Err(self.unexpected(spanned)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(name: &str, column: usize) -> Expression {
+        Expression {
+            kind: ExpressionKind::Reference(name.to_string()),
+            column,
+        }
+    }
+
+    fn number(number: f32, column: usize) -> Expression {
+        Expression {
+            kind: ExpressionKind::Number(number),
+            column,
+        }
+    }
+
+    #[test]
+    fn test_parse_reference() {
+        assert_eq!(
+            reference("normal_bg", 2),
+            parse_expression(" normal_bg ").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_calls() {
+        let expression = parse_expression("darken(mix(red, #1e2132, 0.3), -0.1)").unwrap();
+
+        assert_eq!(
+            Expression {
+                kind: ExpressionKind::Call {
+                    function: "darken".to_string(),
+                    arguments: vec![
+                        Expression {
+                            kind: ExpressionKind::Call {
+                                function: "mix".to_string(),
+                                arguments: vec![
+                                    reference("red", 12),
+                                    Expression {
+                                        kind: ExpressionKind::Hex("#1e2132".to_string()),
+                                        column: 17,
+                                    },
+                                    number(0.3, 26),
+                                ],
+                            },
+                            column: 8,
+                        },
+                        Expression {
+                            kind: ExpressionKind::Negate(Box::new(number(0.1, 33))),
+                            column: 32,
+                        },
+                    ],
+                },
+                column: 1,
+            },
+            expression
+        );
+        assert_eq!(
+            "darken(mix(red, #1e2132, 0.3), -0.1)",
+            expression.to_string()
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        assert_eq!(
+            vec!["red", "bg", "fg"],
+            parse_expression("mix(darken(red, 0.1), mix(bg, fg, 0.5), 0.3)")
+                .unwrap()
+                .references()
+        );
+    }
+
+    #[test]
+    fn test_parse_arithmetic() {
+        let expression = parse_expression("$hue_base + 180 * 2 - -(1 - 0.5) / 2").unwrap();
+
+        assert_eq!(
+            "($hue_base + (180 * 2)) - (-(1 - 0.5) / 2)",
+            expression.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_variable() {
+        assert_eq!(
+            Expression {
+                kind: ExpressionKind::Variable("hue_base".to_string()),
+                column: 1,
+            },
+            parse_expression("$hue_base").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            ThemeError::UnexpectedToken {
+                token: ")".to_string(),
+                column: 12,
+                expression: "darken(red,)".to_string(),
+            },
+            parse_expression("darken(red,)").unwrap_err()
+        );
+        assert_eq!(
+            ThemeError::UnexpectedEnd {
+                expression: "darken(red, 0.1".to_string(),
+            },
+            parse_expression("darken(red, 0.1").unwrap_err()
+        );
+        assert_eq!(
+            ThemeError::UnexpectedToken {
+                token: "blue".to_string(),
+                column: 5,
+                expression: "red blue".to_string(),
+            },
+            parse_expression("red blue").unwrap_err()
+        );
+        assert_eq!(
+            ThemeError::UnexpectedToken {
+                token: "%".to_string(),
+                column: 14,
+                expression: "lighten(red, %)".to_string(),
+            },
+            parse_expression("lighten(red, %)").unwrap_err()
+        );
+    }
+}
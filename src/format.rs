@@ -1,25 +1,147 @@
-use std::{collections::HashMap, fmt::Display, fs, path::Path};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
 
+use anyhow::Context;
 use indexmap::IndexMap;
-use regex::Regex;
 use serde::Deserialize;
-use toml::Table;
+use toml::{Table, Value};
 
 use crate::{
-    color::{mix, Color, HslColor, RgbColor},
-    error::{FileError, ThemeError},
-    highlight::parse_highlight,
+    color::{
+        blend, mix, mix_hsl, mix_linear, mix_oklab, parse_hex, validate_alpha, Color, HslColor,
+        OklchColor, RgbColor,
+    },
+    contrast::{ensure_contrast, readable, Metric},
+    error::{FileError, RgbColorError, ThemeError},
+    expression::{parse_expression, Expression, ExpressionKind, Operator},
+    groups::{closest, expand_pattern, is_known, is_pattern},
+    highlight::{
+        did_you_mean, parse_highlight, parse_highlight_table, resolve_derivations, validate_links,
+        Highlight,
+    },
+    presets::{PRESETS, ROLES},
 };
 
 pub(crate) fn parse_theme(path: &str) -> Result<Theme, anyhow::Error> {
-    if !Path::new(path).exists() {
+    let table = load_theme_table(Path::new(path), &mut Vec::new())?;
+
+    Theme::new(Value::Table(table).try_into()?)
+}
+
+/// The chain holds the files currently being loaded to detect cycles.
+fn load_theme_table(path: &Path, chain: &mut Vec<PathBuf>) -> Result<Table, anyhow::Error> {
+    let mut table = read_table(path, chain)?;
+
+    chain.push(path.canonicalize()?);
+    table = resolve_includes(path, table, chain)?;
+
+    if let Some(extends) = table.remove("extends") {
+        let Some(extends) = extends.as_str() else {
+            return Err(FileError::InvalidExtends {
+                path: path.display().to_string(),
+            }
+            .into());
+        };
+
+        let mut parent = load_theme_table(&relative_to(path, extends), chain)?;
+        merge_sections(&mut parent, table);
+        table = parent;
+    }
+
+    chain.pop();
+
+    Ok(table)
+}
+
+/// Entries of the including table take precedence over the included ones.
+fn resolve_includes(
+    path: &Path,
+    mut table: Table,
+    chain: &mut Vec<PathBuf>,
+) -> Result<Table, anyhow::Error> {
+    let Some(includes) = table.remove("include") else {
+        return Ok(table);
+    };
+
+    let invalid = || FileError::InvalidInclude {
+        path: path.display().to_string(),
+    };
+
+    let mut merged = Table::new();
+
+    for include in includes.as_array().ok_or_else(invalid)? {
+        let include_path = relative_to(path, include.as_str().ok_or_else(invalid)?);
+        let fragment = read_table(&include_path, chain)?;
+
+        chain.push(include_path.canonicalize()?);
+        let fragment = resolve_includes(&include_path, fragment, chain)?;
+        chain.pop();
+
+        if let Some(key) = fragment
+            .keys()
+            .find(|key| !["highlights", "bulk", "globals"].contains(&key.as_str()))
+        {
+            return Err(FileError::InvalidFragment {
+                path: include_path.display().to_string(),
+                key: key.to_string(),
+            }
+            .into());
+        }
+
+        merge_sections(&mut merged, fragment);
+    }
+
+    merge_sections(&mut merged, table);
+
+    Ok(merged)
+}
+
+/// Fails if the file is already being loaded further up the chain.
+fn read_table(path: &Path, chain: &[PathBuf]) -> Result<Table, anyhow::Error> {
+    if !path.exists() {
         return Err(FileError::FileNotFound {
-            path: path.to_string(),
+            path: path.display().to_string(),
+        }
+        .into());
+    }
+
+    let canonical = path.canonicalize()?;
+
+    if let Some(start) = chain.iter().position(|visiting| *visiting == canonical) {
+        let mut cycle: Vec<String> = chain[start..]
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        cycle.push(canonical.display().to_string());
+
+        return Err(FileError::FileCycle {
+            cycle: cycle.join(" -> "),
         }
         .into());
     }
 
-    Theme::new(toml::from_str(&fs::read_to_string(path)?)?)
+    Ok(toml::from_str(&fs::read_to_string(path)?)?)
+}
+
+fn relative_to(path: &Path, reference: &str) -> PathBuf {
+    path.parent().unwrap_or(Path::new("")).join(reference)
+}
+
+/// Sections are merged entry by entry while everything else, including inline highlight tables,
+/// is replaced as a whole.
+fn merge_sections(parent: &mut Table, child: Table) {
+    for (key, value) in child {
+        match (parent.get_mut(&key), value) {
+            (Some(Value::Table(section)), Value::Table(entries)) => section.extend(entries),
+            (_, value) => {
+                parent.insert(key, value);
+            }
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -27,7 +149,11 @@ pub(crate) struct ParsedTheme {
     pub name: String,
     pub background: String,
     pub hues: Option<HashMap<String, f32>>,
+    pub vars: Option<HashMap<String, f32>>,
     pub colors: Table,
+    pub roles: Option<Table>,
+    pub presets: Option<Vec<String>>,
+    pub bulk: Option<Table>,
     pub highlights: Table,
     pub globals: Table,
 }
@@ -44,43 +170,78 @@ pub(crate) fn lookup_color<'a>(
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum GlobalValue {
+    Color(RgbColor),
+    Boolean(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<GlobalValue>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Global {
+    pub name: String,
+    pub value: GlobalValue,
+}
+
 pub(crate) struct Theme {
     pub name: String,
     pub background: Background,
     pub palette: IndexMap<String, Box<dyn Color>>,
-    pub highlights: Vec<String>,
-    pub globals: Vec<String>,
+    pub highlights: Vec<Highlight>,
+    pub globals: Vec<Global>,
+    pub warnings: Vec<String>,
 }
 
 impl Theme {
     fn new(parsed: ParsedTheme) -> Result<Theme, anyhow::Error> {
-        let palette = parse_palette(&parsed)?;
+        let variables = parse_variables(&parsed)?;
+        let palette = parse_palette(&parsed, &variables)?;
+
+        let declared = expand_highlights(&parsed)?;
 
-        let mut highlights: Vec<String> = Vec::new();
-        for (key, value) in &parsed.highlights {
-            match value.as_str() {
-                Some(value) => {
-                    highlights.push(parse_highlight(key, value, &palette)?);
+        let mut warnings: Vec<String> = Vec::new();
+        let mut highlights =
+            parse_presets(&parsed, &declared, &palette, &variables, &mut warnings)?;
+
+        for (key, value) in declared {
+            if !is_known(&key) {
+                warnings.push(format!(
+                    "Highlight {key:?} is not a standard Neovim group{}",
+                    did_you_mean(&key)
+                ));
+            }
+
+            match value {
+                Value::String(value) => highlights.push(parse_highlight(&key, value, &palette)?),
+                Value::Table(table) => {
+                    highlights.push(parse_highlight_table(&key, table, &palette)?)
                 }
-                None => return Err(ThemeError::MissingValue.into()),
+                _ => return Err(ThemeError::MissingValue.into()),
             }
         }
 
-        let mut globals: Vec<String> = Vec::new();
+        validate_links(&highlights)?;
+        resolve_derivations(&mut highlights)?;
+
+        // The special fg and bg values take the colors Normal has when a highlight is set
+        if let Some(index) = highlights
+            .iter()
+            .position(|highlight| highlight.group.eq_ignore_ascii_case("Normal"))
+        {
+            let normal = highlights.remove(index);
+            highlights.insert(0, normal);
+        }
+
+        let mut globals: Vec<Global> = Vec::new();
 
         for (key, value) in &parsed.globals {
-            match value.as_str() {
-                Some(value) => {
-                    let color = if palette.contains_key(value) {
-                        palette[value].hex()
-                    } else {
-                        parse_palette_entry(value, &palette, &parsed.hues)?.hex()
-                    };
-
-                    globals.push(format!("    vim.g.{key} = \"{color}\"\n"));
-                }
-                None => return Err(ThemeError::MissingValue.into()),
-            }
+            globals.push(Global {
+                name: key.to_string(),
+                value: parse_global(key, value, &palette, &variables, &mut warnings)?,
+            });
         }
 
         Ok(Theme {
@@ -89,140 +250,693 @@ impl Theme {
             palette,
             highlights,
             globals,
+            warnings,
         })
     }
 }
 
-fn parse_palette(input: &ParsedTheme) -> Result<IndexMap<String, Box<dyn Color>>, anyhow::Error> {
-    let mut palette: IndexMap<String, Box<dyn Color>> = IndexMap::new();
+/// Explicit highlights take precedence over patterns, which in turn take precedence over bulk
+/// entries.
+pub(crate) fn expand_highlights(
+    input: &ParsedTheme,
+) -> Result<IndexMap<String, &Value>, ThemeError> {
+    let mut declared: IndexMap<String, &Value> = IndexMap::new();
+
+    let mut expand = |group: &str, value| -> Result<(), ThemeError> {
+        if !is_pattern(group) {
+            declared.insert(group.to_string(), value);
+            return Ok(());
+        }
+
+        let groups = expand_pattern(group);
+
+        if groups.is_empty() {
+            return Err(ThemeError::EmptyPattern {
+                pattern: group.to_string(),
+            });
+        }
+
+        for group in groups {
+            declared.insert(group.to_string(), value);
+        }
+
+        Ok(())
+    };
+
+    for (name, entry) in input.bulk.iter().flatten() {
+        let invalid = || ThemeError::InvalidBulk {
+            name: name.to_string(),
+        };
+
+        let groups = entry
+            .get("groups")
+            .and_then(Value::as_array)
+            .ok_or_else(invalid)?;
+        let value = entry
+            .get("value")
+            .filter(|value| value.is_str() || value.is_table())
+            .ok_or_else(invalid)?;
+
+        for group in groups {
+            expand(group.as_str().ok_or_else(invalid)?, value)?;
+        }
+    }
+
+    for (key, value) in input.highlights.iter().filter(|(key, _)| is_pattern(key)) {
+        expand(key, value)?;
+    }
+
+    for (key, value) in input.highlights.iter().filter(|(key, _)| !is_pattern(key)) {
+        expand(key, value)?;
+    }
+
+    Ok(declared)
+}
+
+/// Groups that are also declared in `[highlights]` are left out, as are groups that use a role
+/// the theme doesn't define.
+fn parse_presets(
+    input: &ParsedTheme,
+    declared: &IndexMap<String, &Value>,
+    palette: &IndexMap<String, Box<dyn Color>>,
+    variables: &HashMap<String, f32>,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<Highlight>, anyhow::Error> {
+    let mut roles: IndexMap<String, Box<dyn Color>> = IndexMap::new();
+
+    for (key, value) in input.roles.iter().flatten() {
+        if !ROLES.contains(&key.as_str()) {
+            return Err(ThemeError::UnknownRole {
+                role: key.to_string(),
+            }
+            .into());
+        }
 
-    for (key, value) in &input.colors {
         match value.as_str() {
             Some(value) => {
-                if palette.contains_key(value) {
-                    palette.insert(key.to_string(), palette[value].copy());
-                } else {
-                    palette.insert(
-                        key.to_string(),
-                        parse_palette_entry(value, &palette, &input.hues)?,
-                    );
+                roles.insert(
+                    key.to_string(),
+                    parse_palette_entry(key, value, palette, variables)?,
+                );
+            }
+            None => return Err(ThemeError::MissingValue.into()),
+        }
+    }
+
+    let mut highlights: Vec<Highlight> = Vec::new();
+
+    for name in input.presets.iter().flatten() {
+        let preset = PRESETS
+            .iter()
+            .find(|preset| preset.name == name)
+            .ok_or_else(|| ThemeError::UnknownPreset {
+                preset: name.to_string(),
+            })?;
+
+        let mut skipped = 0;
+        let mut undefined: Vec<String> = Vec::new();
+
+        for (group, value) in preset.highlights {
+            if declared.contains_key(*group) {
+                continue;
+            }
+
+            match parse_highlight(group, value, &roles) {
+                Ok(highlight) => highlights.push(highlight),
+                Err(ThemeError::MissingColor { color }) => {
+                    skipped += 1;
+
+                    if !undefined.contains(&color) {
+                        undefined.push(color);
+                    }
                 }
+                Err(error) => return Err(error.into()),
+            }
+        }
+
+        if skipped > 0 {
+            warnings.push(format!(
+                "Preset {name:?} skips {skipped} of {} groups, undefined roles: {}",
+                preset.highlights.len(),
+                undefined.join(", ")
+            ));
+        }
+    }
+
+    Ok(highlights)
+}
+
+fn parse_variables(input: &ParsedTheme) -> Result<HashMap<String, f32>, ThemeError> {
+    let mut variables = input.hues.clone().unwrap_or_default();
+
+    for (key, value) in input.vars.iter().flatten() {
+        if variables.insert(key.to_string(), *value).is_some() {
+            return Err(ThemeError::DuplicateVariable {
+                variable: key.to_string(),
+            });
+        }
+    }
+
+    Ok(variables)
+}
+
+/// Bare words taken as options by functions, which would be ambiguous as color names.
+const OPTIONS: [&str; 4] = ["hsl", "oklch", "wcag", "apca"];
+
+fn parse_palette(
+    input: &ParsedTheme,
+    variables: &HashMap<String, f32>,
+) -> Result<IndexMap<String, Box<dyn Color>>, anyhow::Error> {
+    let mut expressions: IndexMap<String, Expression> = IndexMap::new();
+
+    for (key, value) in &input.colors {
+        if OPTIONS.contains(&key.to_lowercase().as_str()) {
+            return Err(ThemeError::ReservedColor {
+                color: key.to_string(),
+            }
+            .into());
+        }
+
+        match value.as_str() {
+            Some(value) => {
+                expressions.insert(key.to_string(), parse_expression(value)?);
             }
             None => return Err(ThemeError::MissingValue.into()),
         }
     }
 
-    Ok(palette)
+    let mut resolved: IndexMap<String, Box<dyn Color>> = IndexMap::new();
+
+    for key in resolve_order(&expressions)? {
+        let scope = Scope {
+            palette: &resolved,
+            variables,
+        };
+
+        let color = evaluate_color(&expressions[key], &scope).with_context(|| {
+            ThemeError::InvalidEntry {
+                key: key.to_string(),
+                expression: input.colors[key].as_str().unwrap_or_default().to_string(),
+            }
+        })?;
+        resolved.insert(key.to_string(), color);
+    }
+
+    // Keep the palette in the same order as the theme file
+    Ok(expressions
+        .keys()
+        .map(|key| {
+            let color = resolved
+                .swap_remove(key)
+                .expect("Every color has been resolved");
+            (key.to_string(), color)
+        })
+        .collect())
+}
+
+fn resolve_order(expressions: &IndexMap<String, Expression>) -> Result<Vec<&str>, ThemeError> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Visiting,
+        Visited,
+    }
+
+    fn visit<'a>(
+        key: &'a str,
+        expressions: &'a IndexMap<String, Expression>,
+        states: &mut HashMap<&'a str, State>,
+        path: &mut Vec<&'a str>,
+        order: &mut Vec<&'a str>,
+    ) -> Result<(), ThemeError> {
+        match states.get(key) {
+            Some(State::Visited) => return Ok(()),
+            Some(State::Visiting) => {
+                let start = path
+                    .iter()
+                    .position(|k| *k == key)
+                    .expect("Visiting color is part of the path");
+                let mut cycle = path[start..].to_vec();
+                cycle.push(key);
+
+                return Err(ThemeError::ColorCycle {
+                    cycle: cycle.join(" -> "),
+                });
+            }
+            None => {}
+        }
+
+        states.insert(key, State::Visiting);
+        path.push(key);
+
+        for reference in expressions[key].references() {
+            // Unknown references are reported when the color is evaluated
+            if let Some((reference, _)) = expressions.get_key_value(reference) {
+                visit(reference, expressions, states, path, order)?;
+            }
+        }
+
+        path.pop();
+        states.insert(key, State::Visited);
+        order.push(key);
+
+        Ok(())
+    }
+
+    let mut states = HashMap::new();
+    let mut path = Vec::new();
+    let mut order = Vec::new();
+
+    for key in expressions.keys() {
+        visit(key, expressions, &mut states, &mut path, &mut order)?;
+    }
+
+    Ok(order)
 }
 
 fn parse_palette_entry(
+    key: &str,
     value: &str,
     palette: &IndexMap<String, Box<dyn Color>>,
-    hues: &Option<HashMap<String, f32>>,
+    variables: &HashMap<String, f32>,
 ) -> Result<Box<dyn Color>, anyhow::Error> {
-    lazy_static! {
-        static ref RE: Regex = Regex::new(r"^(?i)(hsl|adjust|lighten|darken|mix)\((.*)\)$")
-            .expect("Color format regex is invalid");
-    }
+    let scope = Scope { palette, variables };
 
-    if value.starts_with('#') {
-        return Ok(Box::new(RgbColor::parse_from_hex(value)?));
-    }
+    evaluate_color(&parse_expression(value)?, &scope).with_context(|| ThemeError::InvalidEntry {
+        key: key.to_string(),
+        expression: value.to_string(),
+    })
+}
 
-    match RE.captures(value) {
-        Some(capture) => match capture[1].to_lowercase().as_str() {
-            "hsl" => Ok(Box::new(parse_hsl_color(
-                split_input(&capture[2], 3)?,
-                hues,
-            )?)),
-            "adjust" => adjust_color(split_input(&capture[2], 3)?, palette),
-            "lighten" => lighten_color(split_input(&capture[2], 2)?, palette),
-            "darken" => darken_color(split_input(&capture[2], 2)?, palette),
-            "mix" => mix_colors(split_input(&capture[2], 3)?, palette),
-            _ => panic!("Unhandled color capture group option"),
+/// Bare words are only colors when the palette has them, so misspelled colors are kept as strings
+/// with a warning. `{ string = "..." }` keeps a color name as text.
+fn parse_global(
+    key: &str,
+    value: &Value,
+    palette: &IndexMap<String, Box<dyn Color>>,
+    variables: &HashMap<String, f32>,
+    warnings: &mut Vec<String>,
+) -> Result<GlobalValue, anyhow::Error> {
+    Ok(match value {
+        Value::Boolean(value) => GlobalValue::Boolean(*value),
+        Value::Integer(value) => GlobalValue::Integer(*value),
+        Value::Float(value) => GlobalValue::Float(*value),
+        Value::Array(values) => GlobalValue::Array(
+            values
+                .iter()
+                .map(|value| parse_global(key, value, palette, variables, warnings))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Table(table) => match (table.len(), table.get("string")) {
+            (1, Some(Value::String(value))) => GlobalValue::String(value.to_string()),
+            _ => {
+                return Err(ThemeError::InvalidGlobal {
+                    global: key.to_string(),
+                }
+                .into())
+            }
         },
-        None => Err(ThemeError::InvalidColor {
-            color: value.to_string(),
+        Value::String(value) => {
+            let is_color = match parse_expression(value).map(|expression| expression.kind) {
+                Ok(ExpressionKind::Hex(_) | ExpressionKind::Call { .. }) => true,
+                Ok(ExpressionKind::Reference(reference)) => {
+                    let is_color = palette.contains_key(&reference);
+
+                    if let Some(known) = closest(&reference, palette.keys().map(String::as_str))
+                        .filter(|_| !is_color)
+                    {
+                        warnings.push(format!(
+                            "Global {key:?} is written as the string {value:?}, did you mean the color {known:?}?"
+                        ));
+                    }
+
+                    is_color
+                }
+                _ => false,
+            };
+
+            if !is_color {
+                return Ok(GlobalValue::String(value.to_string()));
+            }
+
+            let color = parse_palette_entry(key, value, palette, variables)?;
+
+            if color.alpha() < 1.0 {
+                return Err(ThemeError::TranslucentColor {
+                    color: value.to_string(),
+                }
+                .into());
+            }
+
+            GlobalValue::Color(color.to_rgb())
         }
-        .into()),
-    }
+        _ => {
+            return Err(ThemeError::InvalidGlobal {
+                global: key.to_string(),
+            }
+            .into())
+        }
+    })
 }
 
-fn split_input(capture: &str, expected_parts: usize) -> Result<Vec<&str>, ThemeError> {
-    let parts: Vec<&str> = capture.split(',').map(|x| x.trim()).collect();
+struct Scope<'a> {
+    palette: &'a IndexMap<String, Box<dyn Color>>,
+    variables: &'a HashMap<String, f32>,
+}
 
-    if parts.len() != expected_parts {
-        return Err(ThemeError::InvalidColor {
-            color: capture.to_string(),
-        });
+fn evaluate_color(expression: &Expression, scope: &Scope) -> Result<Box<dyn Color>, anyhow::Error> {
+    match &expression.kind {
+        ExpressionKind::Hex(hex) => parse_hex(hex),
+        ExpressionKind::Reference(key) => Ok(lookup_color(key, scope.palette)?.copy()),
+        ExpressionKind::Call {
+            function,
+            arguments,
+        } => call_function(function, arguments, expression.column, scope),
+        ExpressionKind::Number(_)
+        | ExpressionKind::Variable(_)
+        | ExpressionKind::Negate(_)
+        | ExpressionKind::Binary { .. } => Err(ThemeError::ExpectedColor {
+            found: expression.to_string(),
+            column: expression.column,
+        }
+        .into()),
     }
-
-    Ok(parts)
 }
 
-fn parse_hsl_color(
-    parts: Vec<&str>,
-    hues: &Option<HashMap<String, f32>>,
-) -> Result<HslColor, anyhow::Error> {
-    if parts[0].starts_with('$') {
-        let key = &parts[0][1..];
+fn evaluate_number(expression: &Expression, scope: &Scope) -> Result<f32, anyhow::Error> {
+    match &expression.kind {
+        ExpressionKind::Number(number) => Ok(*number),
+        ExpressionKind::Variable(key) => match scope.variables.get(key) {
+            Some(value) => Ok(*value),
+            None => Err(ThemeError::MissingVariable {
+                variable: key.to_string(),
+            }
+            .into()),
+        },
+        ExpressionKind::Negate(operand) => Ok(-evaluate_number(operand, scope)?),
+        ExpressionKind::Binary {
+            operator,
+            left,
+            right,
+        } => {
+            let left = evaluate_number(left, scope)?;
+            let right = evaluate_number(right, scope)?;
 
-        return match hues {
-            Some(hues) => match hues.contains_key(key) {
-                true => Ok(HslColor::new(
-                    hues[key],
-                    parts[1].parse::<f32>()?,
-                    parts[2].parse::<f32>()?,
-                )?),
-                false => Err(ThemeError::MissingHue {
-                    hue: key.to_string(),
+            match operator {
+                Operator::Add => Ok(left + right),
+                Operator::Subtract => Ok(left - right),
+                Operator::Multiply => Ok(left * right),
+                Operator::Divide if right == 0.0 => Err(ThemeError::DivisionByZero {
+                    column: expression.column,
                 }
                 .into()),
-            },
-            None => Err(ThemeError::MissingHueSection {
-                hue: key.to_string(),
+                Operator::Divide => Ok(left / right),
             }
-            .into()),
-        };
+        }
+        _ => Err(ThemeError::ExpectedNumber {
+            found: expression.to_string(),
+            column: expression.column,
+        }
+        .into()),
+    }
+}
+
+fn evaluate_scale(expression: &Expression, scope: &Scope) -> Result<f32, anyhow::Error> {
+    let factor = evaluate_number(expression, scope)?;
+
+    if !(-1.0..=1.0).contains(&factor) {
+        return Err(ThemeError::InvalidScale {
+            found: factor,
+            column: expression.column,
+        }
+        .into());
     }
 
-    Ok(HslColor::new(
-        parts[0].parse::<f32>()?,
-        parts[1].parse::<f32>()?,
-        parts[2].parse::<f32>()?,
-    )?)
+    Ok(factor)
 }
 
-fn adjust_color(
-    parts: Vec<&str>,
-    palette: &IndexMap<String, Box<dyn Color>>,
-) -> Result<Box<dyn Color>, anyhow::Error> {
-    Ok(lookup_color(parts[0], palette)?.adjust(parts[1].parse::<f32>()?, parts[2].parse::<f32>()?))
+fn evaluate_keyword(expression: &Expression) -> Result<String, ThemeError> {
+    match &expression.kind {
+        ExpressionKind::Reference(keyword) => Ok(keyword.to_lowercase()),
+        _ => Err(ThemeError::UnknownOption {
+            option: expression.to_string(),
+            column: expression.column,
+        }),
+    }
 }
 
-fn lighten_color(
-    parts: Vec<&str>,
-    palette: &IndexMap<String, Box<dyn Color>>,
-) -> Result<Box<dyn Color>, anyhow::Error> {
-    Ok(lookup_color(parts[0], palette)?.lighten(parts[1].parse::<f32>()?))
+fn expect_arguments_with_options<'a, const N: usize>(
+    function: &str,
+    arguments: &'a [Expression],
+    column: usize,
+) -> Result<(&'a [Expression; N], &'a [Expression]), ThemeError> {
+    if arguments.len() < N {
+        return Err(ThemeError::ArgumentCount {
+            function: function.to_string(),
+            column,
+            expected: N,
+            found: arguments.len(),
+        });
+    }
+
+    let (required, options) = arguments.split_at(N);
+
+    Ok((expect_arguments(function, required, column)?, options))
 }
 
-fn darken_color(
-    parts: Vec<&str>,
-    palette: &IndexMap<String, Box<dyn Color>>,
+fn expect_arguments<'a, const N: usize>(
+    function: &str,
+    arguments: &'a [Expression],
+    column: usize,
+) -> Result<&'a [Expression; N], ThemeError> {
+    arguments.try_into().map_err(|_| ThemeError::ArgumentCount {
+        function: function.to_string(),
+        column,
+        expected: N,
+        found: arguments.len(),
+    })
+}
+
+fn call_function(
+    function: &str,
+    arguments: &[Expression],
+    column: usize,
+    scope: &Scope,
 ) -> Result<Box<dyn Color>, anyhow::Error> {
-    Ok(lookup_color(parts[0], palette)?.darken(parts[1].parse::<f32>()?))
+    match function.to_lowercase().as_str() {
+        "hsl" => {
+            let [hue, saturation, lightness] = expect_arguments(function, arguments, column)?;
+
+            Ok(Box::new(HslColor::new(
+                evaluate_number(hue, scope)?,
+                evaluate_number(saturation, scope)?,
+                evaluate_number(lightness, scope)?,
+            )?))
+        }
+        "oklch" => {
+            let [lightness, chroma, hue] = expect_arguments(function, arguments, column)?;
+
+            Ok(Box::new(OklchColor::new(
+                evaluate_number(lightness, scope)?,
+                evaluate_number(chroma, scope)?,
+                evaluate_number(hue, scope)?,
+            )?))
+        }
+        "oklab" => {
+            let [lightness, a, b] = expect_arguments(function, arguments, column)?;
+
+            Ok(Box::new(OklchColor::from_oklab(
+                evaluate_number(lightness, scope)?,
+                evaluate_number(a, scope)?,
+                evaluate_number(b, scope)?,
+            )?))
+        }
+        "adjust" => {
+            let [color, saturation, lightness] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.adjust(
+                evaluate_number(saturation, scope)?,
+                evaluate_number(lightness, scope)?,
+            ))
+        }
+        "lighten" => {
+            let [color, amount] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.lighten(evaluate_number(amount, scope)?))
+        }
+        "darken" => {
+            let [color, amount] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.darken(evaluate_number(amount, scope)?))
+        }
+        "scale" => {
+            let [color, saturation, lightness] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.scale(
+                evaluate_scale(saturation, scope)?,
+                evaluate_scale(lightness, scope)?,
+            ))
+        }
+        "lighten_rel" => {
+            let [color, amount] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.scale(0.0, evaluate_scale(amount, scope)?))
+        }
+        "darken_rel" => {
+            let [color, amount] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.scale(0.0, -evaluate_scale(amount, scope)?))
+        }
+        "rotate" => {
+            let [color, degrees] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.rotate(evaluate_number(degrees, scope)?))
+        }
+        "complement" => {
+            let [color] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.rotate(180.0))
+        }
+        "saturate" => {
+            let [color, amount] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.saturate(evaluate_number(amount, scope)?))
+        }
+        "desaturate" => {
+            let [color, amount] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.desaturate(evaluate_number(amount, scope)?))
+        }
+        "grayscale" => {
+            let [color] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.grayscale())
+        }
+        "invert" => {
+            let [color] = expect_arguments(function, arguments, column)?;
+
+            Ok(evaluate_color(color, scope)?.invert())
+        }
+        "alpha" => {
+            let [color, alpha] = expect_arguments(function, arguments, column)?;
+            let alpha = evaluate_number(alpha, scope)?;
+            validate_alpha(alpha)?;
+
+            Ok(evaluate_color(color, scope)?.with_alpha(alpha))
+        }
+        "blend" => match arguments {
+            [foreground, background] => Ok(Box::new(blend(
+                evaluate_color(foreground, scope)?.as_ref(),
+                evaluate_color(background, scope)?.as_ref(),
+                1.0,
+            )?)),
+            _ => {
+                let [foreground, background, alpha] =
+                    expect_arguments(function, arguments, column)?;
+
+                Ok(Box::new(blend(
+                    evaluate_color(foreground, scope)?.as_ref(),
+                    evaluate_color(background, scope)?.as_ref(),
+                    evaluate_number(alpha, scope)?,
+                )?))
+            }
+        },
+        "ensure_contrast" => {
+            let ([color, against, ratio], options) =
+                expect_arguments_with_options(function, arguments, column)?;
+
+            let mut color = evaluate_color(color, scope)?;
+            let against = evaluate_color(against, scope)?;
+            let mut metric = Metric::Wcag;
+
+            for option in options {
+                match evaluate_keyword(option)?.as_str() {
+                    "hsl" => color = Box::new(HslColor::from(color.to_rgb())),
+                    "oklch" => color = Box::new(OklchColor::from(color.to_rgb())),
+                    "wcag" => metric = Metric::Wcag,
+                    "apca" => metric = Metric::Apca,
+                    unknown => {
+                        return Err(ThemeError::UnknownOption {
+                            option: unknown.to_string(),
+                            column: option.column,
+                        }
+                        .into())
+                    }
+                };
+            }
+
+            let ratio_column = ratio.column;
+            let ratio = evaluate_number(ratio, scope)?;
+            let range = metric.range();
+
+            if !range.contains(&ratio) {
+                return Err(ThemeError::InvalidContrast {
+                    found: ratio,
+                    column: ratio_column,
+                    min: *range.start(),
+                    max: *range.end(),
+                }
+                .into());
+            }
+
+            match ensure_contrast(color.as_ref(), against.as_ref(), ratio, metric) {
+                Some(color) => Ok(color),
+                None => Err(ThemeError::UnreachableContrast { ratio, column }.into()),
+            }
+        }
+        "readable" => {
+            let ([background], candidates) =
+                expect_arguments_with_options(function, arguments, column)?;
+
+            let background = evaluate_color(background, scope)?;
+            let candidates = match candidates {
+                [] => vec![
+                    Box::new(RgbColor::new(0, 0, 0)) as Box<dyn Color>,
+                    Box::new(RgbColor::new(255, 255, 255)),
+                ],
+                _ => candidates
+                    .iter()
+                    .map(|candidate| evaluate_color(candidate, scope))
+                    .collect::<Result<Vec<_>, _>>()?,
+            };
+
+            let candidates: Vec<&dyn Color> = candidates.iter().map(|c| c.as_ref()).collect();
+
+            Ok(readable(background.as_ref(), &candidates).copy())
+        }
+        "mix" => mix_colors(expect_arguments(function, arguments, column)?, scope, mix),
+        "mix_linear" => mix_colors(
+            expect_arguments(function, arguments, column)?,
+            scope,
+            mix_linear,
+        ),
+        "mix_oklab" => mix_colors(
+            expect_arguments(function, arguments, column)?,
+            scope,
+            mix_oklab,
+        ),
+        "mix_hsl" => mix_colors(
+            expect_arguments(function, arguments, column)?,
+            scope,
+            mix_hsl,
+        ),
+        _ => Err(ThemeError::UnknownFunction {
+            function: function.to_string(),
+            column,
+        }
+        .into()),
+    }
 }
 
-fn mix_colors(
-    parts: Vec<&str>,
-    palette: &IndexMap<String, Box<dyn Color>>,
+fn mix_colors<C: Color + 'static>(
+    [color1, color2, weight]: &[Expression; 3],
+    scope: &Scope,
+    mix: fn(&dyn Color, &dyn Color, f32) -> Result<C, RgbColorError>,
 ) -> Result<Box<dyn Color>, anyhow::Error> {
     Ok(Box::new(mix(
-        lookup_color(parts[0], palette)?,
-        lookup_color(parts[1], palette)?,
-        parts[2].parse::<f32>()?,
-    )?) as Box<dyn Color>)
+        evaluate_color(color1, scope)?.as_ref(),
+        evaluate_color(color2, scope)?.as_ref(),
+        evaluate_number(weight, scope)?,
+    )?))
 }
 
 #[derive(Debug)]
@@ -253,3 +967,494 @@ impl Display for Background {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::highlight::HighlightColor;
+
+    fn parse(source: &str) -> ParsedTheme {
+        let source = format!("name = \"test\"\nbackground = \"dark\"\n{source}");
+        toml::from_str(&source).unwrap()
+    }
+
+    fn theme_error<T>(result: Result<T, anyhow::Error>) -> Option<ThemeError> {
+        result.err()?.downcast().ok()
+    }
+
+    fn file_error<T>(result: Result<T, anyhow::Error>) -> Option<FileError> {
+        result.err()?.downcast().ok()
+    }
+
+    fn write_files(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("huey-{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        for (file, content) in files {
+            let path = dir.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        dir.canonicalize().unwrap()
+    }
+
+    fn expressions(colors: &[(&str, &str)]) -> IndexMap<String, Expression> {
+        colors
+            .iter()
+            .map(|(key, value)| (key.to_string(), parse_expression(value).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_order() {
+        let colors = expressions(&[
+            ("comment", "mix(fg, bg, 0.5)"),
+            ("fg", "#c6c8d1"),
+            ("bg", "darken(base, 0.05)"),
+            ("base", "#161821"),
+        ]);
+
+        assert_eq!(
+            Ok(vec!["fg", "base", "bg", "comment"]),
+            resolve_order(&colors)
+        );
+
+        let colors = expressions(&[
+            ("a", "lighten(b, 0.1)"),
+            ("b", "mix(c, #000000, 0.5)"),
+            ("c", "a"),
+        ]);
+
+        assert_eq!(
+            Err(ThemeError::ColorCycle {
+                cycle: "a -> b -> c -> a".to_string()
+            }),
+            resolve_order(&colors)
+        );
+    }
+
+    #[test]
+    fn test_parse_palette_rejects_options() {
+        let theme = parse(
+            "[colors]
+            fg = \"#c6c8d1\"
+            OKLCH = \"ensure_contrast(fg, #161821, 4.5, oklch)\"
+            [highlights]
+            [globals]",
+        );
+
+        assert_eq!(
+            Some(ThemeError::ReservedColor {
+                color: "OKLCH".to_string()
+            }),
+            theme_error(parse_palette(&theme, &HashMap::new()))
+        );
+
+        let theme = parse(
+            "[colors]
+            apca = \"#c6c8d1\"
+            comment = \"ensure_contrast(#6b7089, #161821, 60, apca)\"
+            [highlights]
+            [globals]",
+        );
+
+        assert_eq!(
+            Some(ThemeError::ReservedColor {
+                color: "apca".to_string()
+            }),
+            theme_error(parse_palette(&theme, &HashMap::new()))
+        );
+    }
+
+    #[test]
+    fn test_parse_palette_errors_name_the_color() {
+        for (value, cause) in [
+            (
+                "mix(fg, #161821, 0.5, 1)",
+                "Function \"mix\" at column 1 expects 3 arguments, got 4",
+            ),
+            (
+                "lighten(fg, 0.1 / 0)",
+                "Division by zero in expression at column 17",
+            ),
+        ] {
+            let theme = parse(&format!(
+                "[colors]
+                fg = \"#c6c8d1\"
+                comment = \"{value}\"
+                [highlights]
+                [globals]"
+            ));
+
+            let error = parse_palette(&theme, &HashMap::new()).err().unwrap();
+
+            assert_eq!(
+                format!("Invalid value {value:?} for \"comment\": {cause}"),
+                format!("{error:#}")
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_variables() {
+        let theme = parse(
+            "[hues]
+            blue = 220
+            [vars]
+            saturation = 0.3
+            step = 0.05
+            [colors]
+            bg = \"hsl($blue, $saturation, 0.1)\"
+            surface = \"lighten(bg, $step * 2)\"
+            accent = \"hsl($blue + 180, $saturation * 2, 0.6)\"
+            [highlights]
+            [globals]",
+        );
+
+        let variables = parse_variables(&theme).unwrap();
+        assert_eq!(3, variables.len());
+
+        let palette = parse_palette(&theme, &variables).unwrap();
+        let expected = [
+            ("bg", HslColor::new(220.0, 0.3, 0.1).unwrap().to_rgb()),
+            ("surface", HslColor::new(220.0, 0.3, 0.2).unwrap().to_rgb()),
+            ("accent", HslColor::new(40.0, 0.6, 0.6).unwrap().to_rgb()),
+        ];
+
+        for (key, color) in expected {
+            assert_eq!(color.hex(), palette[key].to_rgb().hex());
+        }
+
+        let theme = parse(
+            "[hues]
+            blue = 220
+            [vars]
+            blue = 0.5
+            [colors]
+            [highlights]
+            [globals]",
+        );
+
+        assert_eq!(
+            Err(ThemeError::DuplicateVariable {
+                variable: "blue".to_string()
+            }),
+            parse_variables(&theme)
+        );
+    }
+
+    #[test]
+    fn test_parse_palette_keeps_file_order() {
+        let theme = parse(
+            "[colors]
+            comment = \"mix(fg, bg, 0.5)\"
+            fg = \"#c6c8d1\"
+            bg = \"#161821\"
+            [highlights]
+            [globals]",
+        );
+
+        let palette = parse_palette(&theme, &HashMap::new()).unwrap();
+
+        assert_eq!(
+            vec!["comment", "fg", "bg"],
+            palette.keys().collect::<Vec<_>>()
+        );
+        assert_eq!("#6e7079", palette["comment"].to_rgb().hex());
+    }
+
+    #[test]
+    fn test_merge_sections() {
+        let mut parent: Table = toml::from_str(
+            "name = \"base\"
+            [colors]
+            fg = \"#c6c8d1\"
+            bg = \"#161821\"
+            [highlights]
+            Comment = { fg = \"fg\", italic = true }
+            [globals]
+            terminal_color_0 = \"bg\"",
+        )
+        .unwrap();
+        let child: Table = toml::from_str(
+            "name = \"child\"
+            [colors]
+            bg = \"#000000\"
+            [highlights]
+            Comment = { bg = \"bg\" }",
+        )
+        .unwrap();
+
+        merge_sections(&mut parent, child);
+
+        let expected: Table = toml::from_str(
+            "name = \"child\"
+            [colors]
+            fg = \"#c6c8d1\"
+            bg = \"#000000\"
+            [highlights]
+            Comment = { bg = \"bg\" }
+            [globals]
+            terminal_color_0 = \"bg\"",
+        )
+        .unwrap();
+
+        assert_eq!(expected, parent);
+    }
+
+    #[test]
+    fn test_load_theme_table_extends() {
+        let dir = write_files(
+            "extends",
+            &[
+                ("base.toml", "name = \"base\"\n[colors]\nfg = \"#c6c8d1\""),
+                ("child.toml", "extends = \"base.toml\"\nname = \"child\""),
+                ("orphan.toml", "extends = \"missing.toml\""),
+                ("a.toml", "extends = \"b.toml\""),
+                ("b.toml", "extends = \"a.toml\""),
+            ],
+        );
+
+        let table = load_theme_table(&dir.join("child.toml"), &mut Vec::new()).unwrap();
+        assert_eq!(Some("child"), table["name"].as_str());
+        assert_eq!(Some("#c6c8d1"), table["colors"]["fg"].as_str());
+        assert!(!table.contains_key("extends"));
+
+        assert_eq!(
+            Some(FileError::FileNotFound {
+                path: dir.join("missing.toml").display().to_string()
+            }),
+            file_error(load_theme_table(&dir.join("orphan.toml"), &mut Vec::new()))
+        );
+
+        let cycle = ["a.toml", "b.toml", "a.toml"].map(|file| dir.join(file).display().to_string());
+        assert_eq!(
+            Some(FileError::FileCycle {
+                cycle: cycle.join(" -> ")
+            }),
+            file_error(load_theme_table(&dir.join("a.toml"), &mut Vec::new()))
+        );
+    }
+
+    #[test]
+    fn test_resolve_includes() {
+        let dir = write_files(
+            "include",
+            &[
+                (
+                    "theme.toml",
+                    "include = [\"parts/syntax.toml\"]\n[highlights]\nComment = \"fg\"",
+                ),
+                (
+                    "parts/syntax.toml",
+                    "include = [\"ui.toml\"]\n[highlights]\nComment = \"bg\"\nString = \"fg\"",
+                ),
+                ("parts/ui.toml", "[globals]\nterminal_color_0 = \"bg\""),
+                ("parts/colors.toml", "[colors]\nfg = \"#c6c8d1\""),
+                ("invalid.toml", "include = [\"parts/colors.toml\"]"),
+                ("a.toml", "include = [\"b.toml\"]"),
+                ("b.toml", "include = [\"a.toml\"]"),
+            ],
+        );
+
+        let table = load_theme_table(&dir.join("theme.toml"), &mut Vec::new()).unwrap();
+        let expected: Table = toml::from_str(
+            "[highlights]
+            Comment = \"fg\"
+            String = \"fg\"
+            [globals]
+            terminal_color_0 = \"bg\"",
+        )
+        .unwrap();
+
+        assert_eq!(expected, table);
+
+        assert_eq!(
+            Some(FileError::InvalidFragment {
+                path: dir.join("parts/colors.toml").display().to_string(),
+                key: "colors".to_string()
+            }),
+            file_error(load_theme_table(&dir.join("invalid.toml"), &mut Vec::new()))
+        );
+
+        let cycle = ["a.toml", "b.toml", "a.toml"].map(|file| dir.join(file).display().to_string());
+        assert_eq!(
+            Some(FileError::FileCycle {
+                cycle: cycle.join(" -> ")
+            }),
+            file_error(load_theme_table(&dir.join("a.toml"), &mut Vec::new()))
+        );
+    }
+
+    #[test]
+    fn test_parse_presets() {
+        let generate = |added: &str| {
+            let theme = parse(&format!(
+                "presets = [\"gitsigns\"]
+                [colors]
+                green = \"#b4be82\"
+                yellow = \"#e2a478\"
+                [roles]
+                added = \"{added}\"
+                changed = \"yellow\"
+                [highlights]
+                GitSignsAdd = \"yellow\"
+                [globals]"
+            ));
+
+            let palette = parse_palette(&theme, &HashMap::new()).unwrap();
+            let declared = expand_highlights(&theme).unwrap();
+            let mut warnings = Vec::new();
+            let highlights =
+                parse_presets(&theme, &declared, &palette, &HashMap::new(), &mut warnings).unwrap();
+
+            (highlights, warnings)
+        };
+
+        let (highlights, warnings) = generate("green");
+        let groups: Vec<&str> = highlights.iter().map(|h| h.group.as_str()).collect();
+
+        assert_eq!(
+            vec!["GitSignsChange", "GitSignsAddNr", "GitSignsChangeNr"],
+            groups
+        );
+        assert_eq!(
+            Some("#b4be82".to_string()),
+            highlights[1].fg.and_then(|fg| fg.rgb()).map(|fg| fg.hex())
+        );
+        assert_eq!(
+            vec!["Preset \"gitsigns\" skips 3 of 7 groups, undefined roles: removed, muted"],
+            warnings
+        );
+
+        let (highlights, _) = generate("yellow");
+
+        assert_eq!(
+            Some("#e2a478".to_string()),
+            highlights[1].fg.and_then(|fg| fg.rgb()).map(|fg| fg.hex())
+        );
+    }
+
+    #[test]
+    fn test_parse_global() {
+        let theme = parse(
+            "[colors]
+            blue = \"#84a0c6\"
+            [highlights]
+            [globals]
+            terminal_color_4 = \"blue\"
+            terminal_color_12 = \"lighten(blue, 0.1)\"
+            iceberg_transparent = false
+            iceberg_blend = 10
+            iceberg_ratio = 0.5
+            iceberg_title = \"Iceberg, dark\"
+            iceberg_style = { string = \"soft\" }
+            iceberg_list = [\"#000000\", true, [1]]
+            iceberg_filetypes = [\"markdown\", \"help\"]
+            iceberg_mode = \"dark\"
+            iceberg_accent = { string = \"blue\" }
+            terminal_color_0 = \"blu\"
+            iceberg_table = { text = \"soft\" }",
+        );
+        let palette = parse_palette(&theme, &HashMap::new()).unwrap();
+        let global = |key: &str| {
+            let mut warnings = Vec::new();
+            parse_global(
+                key,
+                &theme.globals[key],
+                &palette,
+                &HashMap::new(),
+                &mut warnings,
+            )
+            .map(|value| (value, warnings))
+        };
+        let value = |key: &str| global(key).unwrap().0;
+
+        assert_eq!(
+            GlobalValue::Color(RgbColor::new(0x84, 0xa0, 0xc6)),
+            value("terminal_color_4")
+        );
+        assert_eq!(
+            GlobalValue::Color(palette["blue"].lighten(0.1).to_rgb()),
+            value("terminal_color_12")
+        );
+        assert_eq!(GlobalValue::Boolean(false), value("iceberg_transparent"));
+        assert_eq!(GlobalValue::Integer(10), value("iceberg_blend"));
+        assert_eq!(GlobalValue::Float(0.5), value("iceberg_ratio"));
+        assert_eq!(
+            GlobalValue::String("Iceberg, dark".to_string()),
+            value("iceberg_title")
+        );
+        assert_eq!(
+            GlobalValue::String("soft".to_string()),
+            value("iceberg_style")
+        );
+        assert_eq!(
+            GlobalValue::Array(vec![
+                GlobalValue::Color(RgbColor::new(0, 0, 0)),
+                GlobalValue::Boolean(true),
+                GlobalValue::Array(vec![GlobalValue::Integer(1)]),
+            ]),
+            value("iceberg_list")
+        );
+        assert_eq!(
+            GlobalValue::Array(vec![
+                GlobalValue::String("markdown".to_string()),
+                GlobalValue::String("help".to_string()),
+            ]),
+            value("iceberg_filetypes")
+        );
+        assert_eq!(
+            GlobalValue::String("dark".to_string()),
+            value("iceberg_mode")
+        );
+        assert_eq!(
+            GlobalValue::String("blue".to_string()),
+            value("iceberg_accent")
+        );
+        assert_eq!(
+            (
+                GlobalValue::String("blu".to_string()),
+                vec![
+                    "Global \"terminal_color_0\" is written as the string \"blu\", did you mean the color \"blue\"?"
+                        .to_string()
+                ]
+            ),
+            global("terminal_color_0").unwrap()
+        );
+        assert_eq!(
+            Some(ThemeError::InvalidGlobal {
+                global: "iceberg_table".to_string()
+            }),
+            theme_error(global("iceberg_table"))
+        );
+    }
+
+    #[test]
+    fn test_theme_sets_normal_first() {
+        let theme = parse(
+            "presets = [\"statusline\"]
+            [colors]
+            white = \"#c6c8d1\"
+            black = \"#161821\"
+            [roles]
+            surface = \"black\"
+            [highlights]
+            Comment = \"fg\"
+            Normal = \"white black\"
+            [globals]",
+        );
+
+        let theme = Theme::new(theme).unwrap();
+        let groups: Vec<&str> = theme.highlights.iter().map(|h| h.group.as_str()).collect();
+
+        assert_eq!(Some(&"Normal"), groups.first());
+        assert!(groups.contains(&"TabLineSel"));
+        assert_eq!(
+            Some(HighlightColor::Foreground),
+            theme.highlights[groups.iter().position(|g| *g == "TabLineSel").unwrap()].fg
+        );
+    }
+}
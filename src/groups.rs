@@ -0,0 +1,528 @@
+pub(crate) struct Category {
+    pub name: &'static str,
+    pub groups: &'static [&'static str],
+}
+
+/// See `:help highlight-groups`, `:help group-name`, `:help diagnostic-highlights`,
+/// `:help treesitter-highlight-groups` and `:help lsp-semantic-highlight`.
+pub(crate) const CATEGORIES: [Category; 5] = [
+    Category {
+        name: "Editor interface",
+        groups: INTERFACE_GROUPS,
+    },
+    Category {
+        name: "Syntax",
+        groups: SYNTAX_GROUPS,
+    },
+    Category {
+        name: "Diagnostics",
+        groups: DIAGNOSTIC_GROUPS,
+    },
+    Category {
+        name: "Treesitter",
+        groups: TREESITTER_GROUPS,
+    },
+    Category {
+        name: "LSP semantic tokens",
+        groups: LSP_GROUPS,
+    },
+];
+
+const INTERFACE_GROUPS: &[&str] = &[
+    "ColorColumn",
+    "Conceal",
+    "CurSearch",
+    "Cursor",
+    "lCursor",
+    "CursorIM",
+    "CursorColumn",
+    "CursorLine",
+    "Directory",
+    "DiffAdd",
+    "DiffChange",
+    "DiffDelete",
+    "DiffText",
+    "EndOfBuffer",
+    "TermCursor",
+    "TermCursorNC",
+    "ErrorMsg",
+    "WinSeparator",
+    "Folded",
+    "FoldColumn",
+    "SignColumn",
+    "IncSearch",
+    "Substitute",
+    "LineNr",
+    "LineNrAbove",
+    "LineNrBelow",
+    "CursorLineNr",
+    "CursorLineFold",
+    "CursorLineSign",
+    "MatchParen",
+    "ModeMsg",
+    "MsgArea",
+    "MsgSeparator",
+    "MoreMsg",
+    "NonText",
+    "Normal",
+    "NormalFloat",
+    "FloatBorder",
+    "FloatTitle",
+    "FloatFooter",
+    "NormalNC",
+    "Pmenu",
+    "PmenuSel",
+    "PmenuKind",
+    "PmenuKindSel",
+    "PmenuExtra",
+    "PmenuExtraSel",
+    "PmenuSbar",
+    "PmenuThumb",
+    "PmenuMatch",
+    "PmenuMatchSel",
+    "Question",
+    "QuickFixLine",
+    "Search",
+    "SnippetTabstop",
+    "SpecialKey",
+    "SpellBad",
+    "SpellCap",
+    "SpellLocal",
+    "SpellRare",
+    "StatusLine",
+    "StatusLineNC",
+    "StatusLineTerm",
+    "StatusLineTermNC",
+    "TabLine",
+    "TabLineFill",
+    "TabLineSel",
+    "Title",
+    "Visual",
+    "VisualNOS",
+    "WarningMsg",
+    "Whitespace",
+    "WildMenu",
+    "WinBar",
+    "WinBarNC",
+    "VertSplit",
+    "Added",
+    "Changed",
+    "Removed",
+];
+
+const SYNTAX_GROUPS: &[&str] = &[
+    "Comment",
+    "Constant",
+    "String",
+    "Character",
+    "Number",
+    "Boolean",
+    "Float",
+    "Identifier",
+    "Function",
+    "Statement",
+    "Conditional",
+    "Repeat",
+    "Label",
+    "Operator",
+    "Keyword",
+    "Exception",
+    "PreProc",
+    "Include",
+    "Define",
+    "Macro",
+    "PreCondit",
+    "Type",
+    "StorageClass",
+    "Structure",
+    "Typedef",
+    "Special",
+    "SpecialChar",
+    "Tag",
+    "Delimiter",
+    "SpecialComment",
+    "Debug",
+    "Underlined",
+    "Ignore",
+    "Error",
+    "Todo",
+];
+
+const DIAGNOSTIC_GROUPS: &[&str] = &[
+    "DiagnosticError",
+    "DiagnosticWarn",
+    "DiagnosticInfo",
+    "DiagnosticHint",
+    "DiagnosticOk",
+    "DiagnosticVirtualTextError",
+    "DiagnosticVirtualTextWarn",
+    "DiagnosticVirtualTextInfo",
+    "DiagnosticVirtualTextHint",
+    "DiagnosticVirtualTextOk",
+    "DiagnosticUnderlineError",
+    "DiagnosticUnderlineWarn",
+    "DiagnosticUnderlineInfo",
+    "DiagnosticUnderlineHint",
+    "DiagnosticUnderlineOk",
+    "DiagnosticFloatingError",
+    "DiagnosticFloatingWarn",
+    "DiagnosticFloatingInfo",
+    "DiagnosticFloatingHint",
+    "DiagnosticFloatingOk",
+    "DiagnosticSignError",
+    "DiagnosticSignWarn",
+    "DiagnosticSignInfo",
+    "DiagnosticSignHint",
+    "DiagnosticSignOk",
+    "DiagnosticDeprecated",
+    "DiagnosticUnnecessary",
+];
+
+const TREESITTER_GROUPS: &[&str] = &[
+    "@variable",
+    "@variable.builtin",
+    "@variable.parameter",
+    "@variable.parameter.builtin",
+    "@variable.member",
+    "@constant",
+    "@constant.builtin",
+    "@constant.macro",
+    "@module",
+    "@module.builtin",
+    "@label",
+    "@string",
+    "@string.documentation",
+    "@string.regexp",
+    "@string.escape",
+    "@string.special",
+    "@string.special.symbol",
+    "@string.special.path",
+    "@string.special.url",
+    "@character",
+    "@character.special",
+    "@boolean",
+    "@number",
+    "@number.float",
+    "@type",
+    "@type.builtin",
+    "@type.definition",
+    "@attribute",
+    "@attribute.builtin",
+    "@property",
+    "@function",
+    "@function.builtin",
+    "@function.call",
+    "@function.macro",
+    "@function.method",
+    "@function.method.call",
+    "@constructor",
+    "@operator",
+    "@keyword",
+    "@keyword.coroutine",
+    "@keyword.function",
+    "@keyword.operator",
+    "@keyword.import",
+    "@keyword.type",
+    "@keyword.modifier",
+    "@keyword.repeat",
+    "@keyword.return",
+    "@keyword.debug",
+    "@keyword.exception",
+    "@keyword.conditional",
+    "@keyword.conditional.ternary",
+    "@keyword.directive",
+    "@keyword.directive.define",
+    "@punctuation.delimiter",
+    "@punctuation.bracket",
+    "@punctuation.special",
+    "@comment",
+    "@comment.documentation",
+    "@comment.error",
+    "@comment.warning",
+    "@comment.todo",
+    "@comment.note",
+    "@markup.strong",
+    "@markup.italic",
+    "@markup.strikethrough",
+    "@markup.underline",
+    "@markup.heading",
+    "@markup.heading.1",
+    "@markup.heading.2",
+    "@markup.heading.3",
+    "@markup.heading.4",
+    "@markup.heading.5",
+    "@markup.heading.6",
+    "@markup.quote",
+    "@markup.math",
+    "@markup.link",
+    "@markup.link.label",
+    "@markup.link.url",
+    "@markup.raw",
+    "@markup.raw.block",
+    "@markup.list",
+    "@markup.list.checked",
+    "@markup.list.unchecked",
+    "@diff.plus",
+    "@diff.minus",
+    "@diff.delta",
+    "@tag",
+    "@tag.builtin",
+    "@tag.attribute",
+    "@tag.delimiter",
+];
+
+const LSP_GROUPS: &[&str] = &[
+    "@lsp.type.class",
+    "@lsp.type.comment",
+    "@lsp.type.decorator",
+    "@lsp.type.enum",
+    "@lsp.type.enumMember",
+    "@lsp.type.event",
+    "@lsp.type.function",
+    "@lsp.type.interface",
+    "@lsp.type.keyword",
+    "@lsp.type.macro",
+    "@lsp.type.method",
+    "@lsp.type.modifier",
+    "@lsp.type.namespace",
+    "@lsp.type.number",
+    "@lsp.type.operator",
+    "@lsp.type.parameter",
+    "@lsp.type.property",
+    "@lsp.type.regexp",
+    "@lsp.type.string",
+    "@lsp.type.struct",
+    "@lsp.type.type",
+    "@lsp.type.typeParameter",
+    "@lsp.type.variable",
+    "@lsp.mod.abstract",
+    "@lsp.mod.async",
+    "@lsp.mod.declaration",
+    "@lsp.mod.defaultLibrary",
+    "@lsp.mod.definition",
+    "@lsp.mod.deprecated",
+    "@lsp.mod.documentation",
+    "@lsp.mod.modification",
+    "@lsp.mod.readonly",
+    "@lsp.mod.static",
+];
+
+/// Treesitter captures and semantic tokens may be specialized with a language suffix such as
+/// `@keyword.rust`, and semantic token types combined with a modifier as in
+/// `@lsp.typemod.function.defaultLibrary`.
+pub(crate) fn is_known(group: &str) -> bool {
+    let standard = |group: &str| {
+        CATEGORIES
+            .iter()
+            .flat_map(|category| category.groups)
+            .any(|known| known.eq_ignore_ascii_case(group))
+    };
+
+    if standard(group) {
+        return true;
+    }
+
+    if let Some(typemod) = group.strip_prefix("@lsp.typemod.") {
+        let mut parts = typemod.split('.');
+
+        return match (parts.next(), parts.next()) {
+            (Some(token), Some(modifier)) => {
+                standard(&format!("@lsp.type.{token}")) && standard(&format!("@lsp.mod.{modifier}"))
+            }
+            _ => false,
+        };
+    }
+
+    group.starts_with('@')
+        && group
+            .match_indices('.')
+            .any(|(index, _)| standard(&group[..index]))
+}
+
+pub(crate) fn is_pattern(group: &str) -> bool {
+    group.contains('*')
+}
+
+/// `*` stands for any sequence of characters, e.g. `@markup.heading.*`.
+pub(crate) fn expand_pattern(pattern: &str) -> Vec<&'static str> {
+    CATEGORIES
+        .iter()
+        .flat_map(|category| category.groups)
+        .copied()
+        .filter(|group| matches_pattern(pattern, group))
+        .collect()
+}
+
+fn matches_pattern(pattern: &str, group: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+
+    let Some(mut rest) = group.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+
+    // Without a wildcard the pattern has to match the whole group
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+pub(crate) fn suggest(group: &str) -> Option<&'static str> {
+    closest(
+        group,
+        CATEGORIES
+            .iter()
+            .flat_map(|category| category.groups.iter().copied()),
+    )
+}
+
+pub(crate) fn closest<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).clamp(1, 3);
+
+    candidates
+        .into_iter()
+        .map(|known| (known, distance(&name.to_lowercase(), &known.to_lowercase())))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(known, _)| known)
+}
+
+/// Levenshtein distance where swapping two adjacent characters counts as a single edit.
+fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::ThemeError,
+        format::{expand_highlights, ParsedTheme},
+    };
+
+    fn parse(source: &str) -> ParsedTheme {
+        let source =
+            format!("name = \"test\"\nbackground = \"dark\"\n[colors]\n[globals]\n{source}");
+        toml::from_str(&source).unwrap()
+    }
+
+    #[test]
+    fn test_is_known() {
+        assert!(is_known("Normal"));
+        assert!(is_known("@keyword.function"));
+        assert!(is_known("@keyword.function.rust"));
+        assert!(is_known("@lsp.type.function.lua"));
+        assert!(is_known("@lsp.typemod.function.defaultLibrary"));
+        assert!(!is_known("@lsp.typemod.function.unknown"));
+        assert!(!is_known("@keywords.function"));
+        assert!(!is_known("Normall"));
+    }
+
+    #[test]
+    fn test_expand_pattern() {
+        assert_eq!(6, expand_pattern("@markup.heading.*").len());
+        assert_eq!(
+            vec!["@keyword.conditional", "@keyword.conditional.ternary"],
+            expand_pattern("@keyword.cond*")
+        );
+        assert_eq!(
+            vec!["DiagnosticSignError"],
+            expand_pattern("Diagnostic*Sign*Error")
+        );
+        assert!(expand_pattern("Telescope*").is_empty());
+    }
+
+    #[test]
+    fn test_expand_highlights() {
+        let theme = parse(
+            "[bulk]
+            diff = { groups = [\"DiffAdd\", \"DiffDelete\", \"@markup.heading.*\"], value = \"red\" }
+            [highlights]
+            \"@markup.heading.*\" = \"blue\"
+            \"@markup.heading.1\" = \"green\"
+            DiffAdd = \"green\"",
+        );
+
+        let declared = expand_highlights(&theme).unwrap();
+        let value = |group: &str| declared[group].as_str();
+
+        assert_eq!(8, declared.len());
+        assert_eq!(Some("red"), value("DiffDelete"));
+        assert_eq!(Some("green"), value("DiffAdd"));
+        assert_eq!(Some("blue"), value("@markup.heading.2"));
+        assert_eq!(Some("green"), value("@markup.heading.1"));
+
+        let theme = parse("[highlights]\n\"Telescope*\" = \"blue\"");
+        assert_eq!(
+            Err(ThemeError::EmptyPattern {
+                pattern: "Telescope*".to_string()
+            }),
+            expand_highlights(&theme)
+        );
+
+        for bulk in [
+            "diff = { value = \"red\" }",
+            "diff = { groups = \"DiffAdd\", value = \"red\" }",
+            "diff = { groups = [\"DiffAdd\", 1], value = \"red\" }",
+            "diff = { groups = [\"DiffAdd\"], value = 1 }",
+        ] {
+            let theme = parse(&format!("[bulk]\n{bulk}\n[highlights]"));
+            assert_eq!(
+                Err(ThemeError::InvalidBulk {
+                    name: "diff".to_string()
+                }),
+                expand_highlights(&theme)
+            );
+        }
+    }
+
+    #[test]
+    fn test_suggest() {
+        assert_eq!(Some("Normal"), suggest("Normall"));
+        assert_eq!(Some("DiagnosticWarn"), suggest("DiagnosticWarning"));
+        assert_eq!(Some("@comment.todo"), suggest("@coment.todo"));
+        assert_eq!(Some("Title"), suggest("Tilte"));
+        assert_eq!(None, suggest("TelescopeBorder"));
+    }
+}
@@ -1,86 +1,574 @@
+use std::collections::HashMap;
+
 use indexmap::IndexMap;
+use toml::{Table, Value};
+
+use crate::{
+    color::{Color, RgbColor},
+    error::ThemeError,
+    format::lookup_color,
+    groups::{is_known, suggest},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum HighlightColor {
+    None,
+    /// The foreground of the `Normal` group
+    Foreground,
+    /// The background of the `Normal` group
+    Background,
+    Color(RgbColor),
+}
+
+impl HighlightColor {
+    pub(crate) fn rgb(&self) -> Option<RgbColor> {
+        match self {
+            HighlightColor::Color(color) => Some(*color),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CtermColor {
+    Index(u8),
+    Name(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Style {
+    Bold,
+    Italic,
+    Underline,
+    Undercurl,
+    Underdouble,
+    Underdotted,
+    Underdashed,
+    Standout,
+    Strikethrough,
+    Nocombine,
+    Reverse,
+    Altfont,
+}
+
+impl Style {
+    const ALL: [Style; 12] = [
+        Style::Bold,
+        Style::Italic,
+        Style::Underline,
+        Style::Undercurl,
+        Style::Underdouble,
+        Style::Underdotted,
+        Style::Underdashed,
+        Style::Standout,
+        Style::Strikethrough,
+        Style::Nocombine,
+        Style::Reverse,
+        Style::Altfont,
+    ];
+
+    fn from_name(name: &str) -> Option<Style> {
+        Style::ALL.into_iter().find(|style| style.name() == name)
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Style::Bold => "bold",
+            Style::Italic => "italic",
+            Style::Underline => "underline",
+            Style::Undercurl => "undercurl",
+            Style::Underdouble => "underdouble",
+            Style::Underdotted => "underdotted",
+            Style::Underdashed => "underdashed",
+            Style::Standout => "standout",
+            Style::Strikethrough => "strikethrough",
+            Style::Nocombine => "nocombine",
+            Style::Reverse => "reverse",
+            Style::Altfont => "altfont",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Highlight {
+    pub group: String,
+    pub fg: Option<HighlightColor>,
+    pub bg: Option<HighlightColor>,
+    pub sp: Option<HighlightColor>,
+    /// `false` explicitly disables a style
+    pub styles: IndexMap<Style, bool>,
+    pub link: Option<String>,
+    pub blend: Option<u8>,
+    pub ctermfg: Option<CtermColor>,
+    pub ctermbg: Option<CtermColor>,
+    pub cterm: Option<IndexMap<Style, bool>>,
+    pub default: Option<bool>,
+    pub force: Option<bool>,
+    /// Resolved once all highlights are parsed
+    pub from: Option<String>,
+}
+
+impl Highlight {
+    pub(crate) fn new(group: &str) -> Highlight {
+        Highlight {
+            group: group.to_string(),
+            fg: None,
+            bg: None,
+            sp: None,
+            styles: IndexMap::new(),
+            link: None,
+            blend: None,
+            ctermfg: None,
+            ctermbg: None,
+            cterm: None,
+            default: None,
+            force: None,
+            from: None,
+        }
+    }
+
+    pub(crate) fn has_style(&self, style: Style) -> bool {
+        self.styles.get(&style).copied().unwrap_or(false)
+    }
+
+    /// Terminal attributes are only taken along with the attributes they stand in for, so that
+    /// overridden ones get a fresh fallback.
+    fn inherit(&mut self, base: &Highlight) {
+        if self.fg.is_none() && self.ctermfg.is_none() {
+            self.ctermfg = base.ctermfg.clone();
+        }
+
+        if self.bg.is_none() && self.ctermbg.is_none() {
+            self.ctermbg = base.ctermbg.clone();
+        }
+
+        if self.styles.is_empty() && self.cterm.is_none() {
+            self.cterm = base.cterm.clone();
+        }
 
-use crate::{color::Color, error::ThemeError, format::lookup_color};
+        let mut styles = base.styles.clone();
+        styles.extend(self.styles.drain(..));
+
+        self.fg = self.fg.or(base.fg);
+        self.bg = self.bg.or(base.bg);
+        self.sp = self.sp.or(base.sp);
+        self.styles = styles;
+        self.blend = self.blend.or(base.blend);
+        self.default = self.default.or(base.default);
+        self.force = self.force.or(base.force);
+        self.from = None;
+    }
+}
 
 pub(crate) fn parse_highlight(
     hl_group: &str,
     value: &str,
     palette: &IndexMap<String, Box<dyn Color>>,
-) -> Result<String, ThemeError> {
+) -> Result<Highlight, ThemeError> {
     let values = value
         .split(' ')
         .filter(|x| !x.is_empty())
         .collect::<Vec<&str>>();
 
+    let mut highlight = Highlight::new(hl_group);
+
     match values[..] {
         [fg] => match fg.contains("link:") {
-            true => Ok(format!(
-                "\n    hl(0, \"{hl_group}\", {{ link = \"{link}\" }})",
-                link = fg.to_string().replace("link:", ""),
-            )),
-            false => Ok(format!(
-                "\n    hl(0, \"{hl_group}\", {{ fg = \"{fg}\", bg = \"NONE\" }})",
-                fg = lookup_highlight(fg, palette)?,
-            )),
+            true => highlight.link = Some(fg.to_string().replace("link:", "")),
+            false => {
+                highlight.fg = Some(lookup_highlight(fg, palette)?);
+                highlight.bg = Some(HighlightColor::None);
+            }
         },
-        [fg, bg] => Ok(format!(
-            "\n    hl(0, \"{hl_group}\", {{ fg = \"{fg}\", bg = \"{bg}\" }})",
-            fg = lookup_highlight(fg, palette)?,
-            bg = lookup_highlight(bg, palette)?,
-        )),
-        [fg, bg, style] => Ok(format!(
-            "\n    hl(0, \"{hl_group}\", {{ fg = \"{fg}\", bg = \"{bg}\", {style_options} }})",
-            fg = lookup_highlight(fg, palette)?,
-            bg = lookup_highlight(bg, palette)?,
-            style_options = parse_style_options(style)?,
-        )),
-        [fg, bg, style, sp] => Ok(format!(
-            "\n    hl(0, \"{hl_group}\", {{ fg = \"{fg}\", bg = \"{bg}\", sp = \"{sp}\", {style_options} }})",
-            fg = lookup_highlight(fg, palette)?,
-            bg = lookup_highlight(bg, palette)?,
-            sp = lookup_highlight(sp, palette)?,
-            style_options = parse_style_options(style)?,
-        )),
-        _ => Err(ThemeError::InvalidHighlight {
-            highlight: value.to_string(),
-        }),
+        [fg, bg] => {
+            highlight.fg = Some(lookup_highlight(fg, palette)?);
+            highlight.bg = Some(lookup_highlight(bg, palette)?);
+        }
+        [base, "+", style] => {
+            highlight.from = Some(base.to_string());
+            highlight.styles = parse_style_options(style)?;
+        }
+        [fg, bg, style] => {
+            highlight.fg = Some(lookup_highlight(fg, palette)?);
+            highlight.bg = Some(lookup_highlight(bg, palette)?);
+            highlight.styles = parse_style_options(style)?;
+        }
+        [fg, bg, style, sp] => {
+            highlight.fg = Some(lookup_highlight(fg, palette)?);
+            highlight.bg = Some(lookup_highlight(bg, palette)?);
+            highlight.sp = Some(lookup_highlight(sp, palette)?);
+            highlight.styles = parse_style_options(style)?;
+        }
+        _ => {
+            return Err(ThemeError::InvalidHighlight {
+                highlight: value.to_string(),
+            })
+        }
+    }
+
+    Ok(highlight)
+}
+
+pub(crate) fn parse_highlight_table(
+    hl_group: &str,
+    table: &Table,
+    palette: &IndexMap<String, Box<dyn Color>>,
+) -> Result<Highlight, ThemeError> {
+    let mut highlight = Highlight::new(hl_group);
+
+    for (key, value) in table {
+        let invalid = |expected: &str| ThemeError::InvalidAttribute {
+            attribute: key.to_string(),
+            group: hl_group.to_string(),
+            expected: expected.to_string(),
+        };
+
+        let string = || value.as_str().ok_or_else(|| invalid("a string"));
+        let boolean = || value.as_bool().ok_or_else(|| invalid("a boolean"));
+
+        match key.as_str() {
+            "fg" => highlight.fg = Some(lookup_highlight(string()?, palette)?),
+            "bg" => highlight.bg = Some(lookup_highlight(string()?, palette)?),
+            "sp" => highlight.sp = Some(lookup_highlight(string()?, palette)?),
+            "link" => highlight.link = Some(string()?.to_string()),
+            "from" => highlight.from = Some(string()?.to_string()),
+            "blend" => {
+                highlight.blend = Some(
+                    value
+                        .as_integer()
+                        .filter(|blend| (0..=100).contains(blend))
+                        .ok_or_else(|| invalid("an integer between 0 and 100"))?
+                        as u8,
+                )
+            }
+            "ctermfg" | "ctermbg" => {
+                let color = match value {
+                    Value::Integer(index) => u8::try_from(*index).ok().map(CtermColor::Index),
+                    Value::String(name) => Some(CtermColor::Name(name.to_string())),
+                    _ => None,
+                }
+                .ok_or_else(|| invalid("an integer between 0 and 255 or a color name"))?;
+
+                match key.as_str() {
+                    "ctermfg" => highlight.ctermfg = Some(color),
+                    _ => highlight.ctermbg = Some(color),
+                }
+            }
+            "cterm" => {
+                let mut styles = IndexMap::new();
+
+                for name in value
+                    .as_array()
+                    .ok_or_else(|| invalid("a list of styles"))?
+                {
+                    let style = name
+                        .as_str()
+                        .and_then(Style::from_name)
+                        .ok_or_else(|| invalid("a list of styles"))?;
+
+                    styles.insert(style, true);
+                }
+
+                highlight.cterm = Some(styles);
+            }
+            "default" => highlight.default = Some(boolean()?),
+            "force" => highlight.force = Some(boolean()?),
+            _ => {
+                let style = Style::from_name(key).ok_or_else(|| ThemeError::UnknownAttribute {
+                    attribute: key.to_string(),
+                    group: hl_group.to_string(),
+                })?;
+
+                highlight.styles.insert(style, boolean()?);
+            }
+        }
+    }
+
+    // Neovim ignores everything but these when linking
+    if highlight.link.is_some()
+        && table
+            .keys()
+            .any(|key| !["link", "default", "force"].contains(&key.as_str()))
+    {
+        return Err(ThemeError::LinkWithAttributes {
+            group: hl_group.to_string(),
+        });
+    }
+
+    Ok(highlight)
+}
+
+pub(crate) fn validate_links(highlights: &[Highlight]) -> Result<(), ThemeError> {
+    let links: HashMap<&str, Option<&str>> = highlights
+        .iter()
+        .map(|highlight| (highlight.group.as_str(), highlight.link.as_deref()))
+        .collect();
+
+    for highlight in highlights {
+        let mut path = vec![highlight.group.as_str()];
+
+        while let Some(Some(target)) = links.get(path[path.len() - 1]) {
+            if let Some(start) = path.iter().position(|group| group == target) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(target);
+
+                return Err(ThemeError::LinkCycle {
+                    cycle: cycle.join(" -> "),
+                });
+            }
+
+            path.push(target);
+        }
+
+        let last = path[path.len() - 1];
+
+        if !links.contains_key(last) && !is_known(last) {
+            return Err(ThemeError::UnknownLinkTarget {
+                group: path[path.len() - 2].to_string(),
+                target: last.to_string(),
+                suggestion: did_you_mean(last),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn did_you_mean(group: &str) -> String {
+    suggest(group)
+        .map(|known| format!(", did you mean {known:?}?"))
+        .unwrap_or_default()
+}
+
+/// Links have to be validated first.
+pub(crate) fn resolve_derivations(highlights: &mut [Highlight]) -> Result<(), ThemeError> {
+    fn resolve(
+        index: usize,
+        highlights: &mut [Highlight],
+        positions: &HashMap<String, usize>,
+        path: &mut Vec<String>,
+    ) -> Result<(), ThemeError> {
+        let Some(mut base) = highlights[index].from.clone() else {
+            return Ok(());
+        };
+
+        let group = highlights[index].group.clone();
+
+        if let Some(start) = path.iter().position(|visiting| *visiting == group) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(group);
+
+            return Err(ThemeError::DerivationCycle {
+                cycle: cycle.join(" -> "),
+            });
+        }
+
+        path.push(group.clone());
+
+        let base_index = loop {
+            let Some(&base_index) = positions.get(&base) else {
+                return Err(ThemeError::UnknownBase { group, base });
+            };
+
+            resolve(base_index, highlights, positions, path)?;
+
+            match &highlights[base_index].link {
+                Some(link) => base = link.clone(),
+                None => break base_index,
+            }
+        };
+
+        path.pop();
+
+        let base = highlights[base_index].clone();
+        highlights[index].inherit(&base);
+
+        Ok(())
+    }
+
+    let positions: HashMap<String, usize> = highlights
+        .iter()
+        .enumerate()
+        .map(|(index, highlight)| (highlight.group.clone(), index))
+        .collect();
+
+    for index in 0..highlights.len() {
+        resolve(index, highlights, &positions, &mut Vec::new())?;
     }
+
+    Ok(())
 }
 
 fn lookup_highlight(
     value: &str,
     palette: &IndexMap<String, Box<dyn Color>>,
-) -> Result<String, ThemeError> {
+) -> Result<HighlightColor, ThemeError> {
     match value {
-        "-" => Ok("NONE".to_string()),
-        _ => Ok(lookup_color(value, palette)?.hex()),
+        "-" | "NONE" => Ok(HighlightColor::None),
+        "fg" if !palette.contains_key(value) => Ok(HighlightColor::Foreground),
+        "bg" if !palette.contains_key(value) => Ok(HighlightColor::Background),
+        _ => {
+            let color = lookup_color(value, palette)?;
+
+            if color.alpha() < 1.0 {
+                return Err(ThemeError::TranslucentColor {
+                    color: value.to_string(),
+                });
+            }
+
+            Ok(HighlightColor::Color(color.to_rgb()))
+        }
     }
 }
 
-fn parse_style_options(style: &str) -> Result<String, ThemeError> {
-    let mut style_options: Vec<&str> = Vec::new();
+fn parse_style_options(style: &str) -> Result<IndexMap<Style, bool>, ThemeError> {
+    let mut style_options: IndexMap<Style, bool> = IndexMap::new();
 
     for option in style.chars() {
-        match option {
-            'b' => style_options.push("bold = true"),
-            'i' => style_options.push("italic = true"),
-            'u' => style_options.push("underline = true"),
-            'c' => style_options.push("undercurl = true"),
-            'd' => style_options.push("underdouble = true"),
-            't' => style_options.push("underdotted = true"),
-            'h' => style_options.push("underdashed = true"),
-            'o' => style_options.push("standout = true"),
-            's' => style_options.push("strikethrough = true"),
-            'n' => style_options.push("nocombine = true"),
-            'r' => style_options.push("reverse = true"),
-            '-' => {}
+        let style = match option {
+            'b' => Style::Bold,
+            'i' => Style::Italic,
+            'u' => Style::Underline,
+            'c' => Style::Undercurl,
+            'd' => Style::Underdouble,
+            't' => Style::Underdotted,
+            'h' => Style::Underdashed,
+            'o' => Style::Standout,
+            's' => Style::Strikethrough,
+            'n' => Style::Nocombine,
+            'r' => Style::Reverse,
+            '-' => continue,
             unknown => {
                 return Err(ThemeError::UnknownStyleOption {
                     option: unknown.to_string(),
                 })
             }
-        }
+        };
+
+        style_options.insert(style, true);
+    }
+
+    Ok(style_options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::RgbColor;
+
+    #[test]
+    fn test_parse_highlight_table() {
+        let mut palette: IndexMap<String, Box<dyn Color>> = IndexMap::new();
+        palette.insert(
+            "red".to_string(),
+            Box::new(RgbColor::parse_from_hex("#e27878").unwrap()),
+        );
+
+        let table: Table = toml::from_str("bg = \"NONE\"\nsp = \"red\"\nitalic = false").unwrap();
+        let highlight = parse_highlight_table("Error", &table, &palette).unwrap();
+
+        assert_eq!(None, highlight.fg);
+        assert_eq!(Some(HighlightColor::None), highlight.bg);
+        assert_eq!(
+            Some("#e27878".to_string()),
+            highlight.sp.and_then(|sp| sp.rgb()).map(|sp| sp.hex())
+        );
+        assert_eq!(Some(&false), highlight.styles.get(&Style::Italic));
+
+        let table: Table = toml::from_str("link = \"Normal\"\nbold = true").unwrap();
+        assert_eq!(
+            Err(ThemeError::LinkWithAttributes {
+                group: "Error".to_string()
+            }),
+            parse_highlight_table("Error", &table, &palette)
+        );
     }
 
-    Ok(style_options.join(", "))
+    #[test]
+    fn test_validate_links() {
+        let link = |group: &str, target: &str| {
+            let mut highlight = Highlight::new(group);
+            highlight.link = Some(target.to_string());
+            highlight
+        };
+
+        assert_eq!(
+            Ok(()),
+            validate_links(&[link("A", "B"), link("B", "Comment")])
+        );
+        assert_eq!(
+            Err(ThemeError::UnknownLinkTarget {
+                group: "B".to_string(),
+                target: "Commnet".to_string(),
+                suggestion: ", did you mean \"Comment\"?".to_string()
+            }),
+            validate_links(&[link("A", "B"), link("B", "Commnet")])
+        );
+        assert_eq!(
+            Err(ThemeError::LinkCycle {
+                cycle: "A -> B -> A".to_string()
+            }),
+            validate_links(&[link("A", "B"), link("B", "A")])
+        );
+    }
+
+    #[test]
+    fn test_resolve_derivations() {
+        let red = RgbColor::parse_from_hex("#e27878").unwrap();
+
+        let mut line_nr = Highlight::new("LineNr");
+        line_nr.fg = Some(HighlightColor::Color(red));
+        line_nr.styles.insert(Style::Italic, true);
+
+        let mut link = Highlight::new("Link");
+        link.link = Some("LineNr".to_string());
+
+        let mut derived = Highlight::new("CursorLineNr");
+        derived.from = Some("Link".to_string());
+        derived.bg = Some(HighlightColor::None);
+        derived.styles.insert(Style::Bold, true);
+
+        let mut highlights = vec![derived, line_nr, link];
+        resolve_derivations(&mut highlights).unwrap();
+
+        assert_eq!(Some(HighlightColor::Color(red)), highlights[0].fg);
+        assert_eq!(Some(HighlightColor::None), highlights[0].bg);
+        assert!(highlights[0].has_style(Style::Italic));
+        assert!(highlights[0].has_style(Style::Bold));
+        assert_eq!(None, highlights[0].from);
+    }
+
+    #[test]
+    fn test_resolve_derivations_cterm() {
+        let mut line_nr = Highlight::new("LineNr");
+        line_nr.fg = Some(HighlightColor::Color(RgbColor::new(0x6b, 0x70, 0x89)));
+        line_nr.bg = Some(HighlightColor::Color(RgbColor::new(0x1e, 0x21, 0x32)));
+        line_nr.styles.insert(Style::Italic, true);
+        line_nr.ctermfg = Some(CtermColor::Index(60));
+        line_nr.ctermbg = Some(CtermColor::Index(235));
+        line_nr.cterm = Some(IndexMap::from([(Style::Italic, true)]));
+
+        let mut cursor_line_nr = Highlight::new("CursorLineNr");
+        cursor_line_nr.from = Some("LineNr".to_string());
+        cursor_line_nr.fg = Some(HighlightColor::Color(RgbColor::new(0xe2, 0xa4, 0x78)));
+        cursor_line_nr.styles.insert(Style::Bold, true);
+
+        let mut fold_column = Highlight::new("FoldColumn");
+        fold_column.from = Some("LineNr".to_string());
+        fold_column.bg = Some(HighlightColor::None);
+
+        let mut highlights = vec![cursor_line_nr, fold_column, line_nr];
+        resolve_derivations(&mut highlights).unwrap();
+
+        assert_eq!(None, highlights[0].ctermfg);
+        assert_eq!(Some(CtermColor::Index(235)), highlights[0].ctermbg);
+        assert_eq!(None, highlights[0].cterm);
+
+        assert_eq!(Some(CtermColor::Index(60)), highlights[1].ctermfg);
+        assert_eq!(None, highlights[1].ctermbg);
+        assert_eq!(
+            Some(IndexMap::from([(Style::Italic, true)])),
+            highlights[1].cterm
+        );
+    }
 }
@@ -0,0 +1,198 @@
+use indexmap::IndexMap;
+
+use crate::{
+    color::Color,
+    format::{Global, GlobalValue},
+    highlight::{CtermColor, Highlight, HighlightColor, Style},
+};
+
+fn render_color(color: &HighlightColor) -> String {
+    match color {
+        HighlightColor::None => "NONE".to_string(),
+        HighlightColor::Foreground => "fg".to_string(),
+        HighlightColor::Background => "bg".to_string(),
+        HighlightColor::Color(color) => color.hex(),
+    }
+}
+
+fn render_cterm_color(color: &CtermColor) -> String {
+    match color {
+        CtermColor::Index(index) => index.to_string(),
+        CtermColor::Name(name) => format!("\"{name}\""),
+    }
+}
+
+fn render_styles(styles: &IndexMap<Style, bool>) -> impl Iterator<Item = String> + '_ {
+    styles
+        .iter()
+        .map(|(style, enabled)| format!("{} = {enabled}", style.name()))
+}
+
+/// `nvim_set_hl` is aliased as `hl` in the generated file.
+pub(crate) fn render_highlight(highlight: &Highlight) -> String {
+    let group = &highlight.group;
+    let mut attributes: Vec<String> = Vec::new();
+
+    if let Some(link) = &highlight.link {
+        attributes.push(format!("link = \"{link}\""));
+    }
+
+    for (key, color) in [
+        ("fg", &highlight.fg),
+        ("bg", &highlight.bg),
+        ("sp", &highlight.sp),
+    ] {
+        if let Some(color) = color {
+            attributes.push(format!("{key} = \"{}\"", render_color(color)));
+        }
+    }
+
+    if let Some(blend) = highlight.blend {
+        attributes.push(format!("blend = {blend}"));
+    }
+
+    attributes.extend(render_styles(&highlight.styles));
+
+    if let Some(cterm) = &highlight.cterm {
+        let styles: Vec<String> = render_styles(cterm).collect();
+
+        match styles.is_empty() {
+            true => attributes.push("cterm = {}".to_string()),
+            false => attributes.push(format!("cterm = {{ {} }}", styles.join(", "))),
+        }
+    }
+
+    for (key, color) in [
+        ("ctermfg", &highlight.ctermfg),
+        ("ctermbg", &highlight.ctermbg),
+    ] {
+        if let Some(color) = color {
+            attributes.push(format!("{key} = {}", render_cterm_color(color)));
+        }
+    }
+
+    for (key, flag) in [("default", highlight.default), ("force", highlight.force)] {
+        if let Some(flag) = flag {
+            attributes.push(format!("{key} = {flag}"));
+        }
+    }
+
+    format!(
+        "    hl(0, \"{group}\", {{ {attributes} }})",
+        attributes = attributes.join(", ")
+    )
+}
+
+pub(crate) fn render_global(global: &Global) -> String {
+    format!(
+        "    vim.g.{} = {}",
+        global.name,
+        render_value(&global.value)
+    )
+}
+
+fn render_value(value: &GlobalValue) -> String {
+    match value {
+        GlobalValue::Color(color) => render_string(&color.hex()),
+        GlobalValue::Boolean(value) => value.to_string(),
+        GlobalValue::Integer(value) => value.to_string(),
+        GlobalValue::Float(value) if value.is_nan() => "0 / 0".to_string(),
+        GlobalValue::Float(value) if value.is_infinite() => match value.is_sign_positive() {
+            true => "math.huge".to_string(),
+            false => "-math.huge".to_string(),
+        },
+        GlobalValue::Float(value) => format!("{value:?}"),
+        GlobalValue::String(value) => render_string(value),
+        GlobalValue::Array(values) if values.is_empty() => "{}".to_string(),
+        GlobalValue::Array(values) => {
+            let values: Vec<String> = values.iter().map(render_value).collect();
+            format!("{{ {} }}", values.join(", "))
+        }
+    }
+}
+
+fn render_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::RgbColor;
+
+    #[test]
+    fn test_render_highlight() {
+        let mut highlight = Highlight::new("Comment");
+        highlight.fg = Some(HighlightColor::Color(
+            RgbColor::parse_from_hex("#6b7089").unwrap(),
+        ));
+        highlight.bg = Some(HighlightColor::None);
+        highlight.styles.insert(Style::Italic, true);
+
+        assert_eq!(
+            "    hl(0, \"Comment\", { fg = \"#6b7089\", bg = \"NONE\", italic = true })",
+            render_highlight(&highlight)
+        );
+    }
+
+    #[test]
+    fn test_render_attributes() {
+        let mut highlight = Highlight::new("Pmenu");
+        highlight.fg = Some(HighlightColor::Background);
+        highlight.blend = Some(10);
+        highlight.cterm = Some(IndexMap::from([(Style::Bold, true)]));
+        highlight.ctermbg = Some(CtermColor::Index(235));
+        highlight.default = Some(true);
+
+        assert_eq!(
+            "    hl(0, \"Pmenu\", { fg = \"bg\", blend = 10, cterm = { bold = true }, ctermbg = 235, default = true })",
+            render_highlight(&highlight)
+        );
+    }
+
+    #[test]
+    fn test_render_link() {
+        let mut highlight = Highlight::new("Whitespace");
+        highlight.link = Some("NonText".to_string());
+
+        assert_eq!(
+            "    hl(0, \"Whitespace\", { link = \"NonText\" })",
+            render_highlight(&highlight)
+        );
+    }
+
+    #[test]
+    fn test_render_global() {
+        let global = Global {
+            name: "theme_options".to_string(),
+            value: GlobalValue::Array(vec![
+                GlobalValue::Color(RgbColor::new(255, 0, 0)),
+                GlobalValue::Boolean(false),
+                GlobalValue::Integer(3),
+                GlobalValue::Float(0.5),
+                GlobalValue::String("say \"hi\"".to_string()),
+                GlobalValue::Array(Vec::new()),
+            ]),
+        };
+
+        assert_eq!(
+            "    vim.g.theme_options = { \"#ff0000\", false, 3, 0.5, \"say \\\"hi\\\"\", {} }",
+            render_global(&global)
+        );
+    }
+}
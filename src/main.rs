@@ -8,31 +8,113 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use format::{Background, Theme};
 
+mod check;
 mod color;
+mod contrast;
+mod cterm;
 mod error;
+mod expression;
 mod format;
+mod groups;
 mod highlight;
+mod lua;
+mod presets;
+mod vision;
 
 #[derive(Debug, Parser)]
-#[clap(author, version, about)]
+#[clap(
+    author,
+    version,
+    about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct Args {
     /// The input colorscheme file
-    pub filename: String,
+    #[clap(required = true)]
+    pub filename: Option<String>,
     /// Directory of generated colorscheme, default to the current working directory
     pub output: Option<String>,
     /// Overwrite the init.lua file if it already exists
     #[clap(long)]
     pub overwrite_init: bool,
+    /// Don't generate the 256 color fallback for terminals without true color support
+    #[clap(long)]
+    pub no_cterm: bool,
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Check the highlights of a colorscheme for accessibility issues
+    Check(CheckArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct CheckArgs {
+    /// The input colorscheme file
+    pub filename: String,
+    /// Minimum WCAG 2.x contrast ratio between the foreground and background of a highlight
+    #[clap(long, default_value_t = 4.5)]
+    pub min_contrast: f32,
+    /// Minimum absolute APCA lightness contrast (Lc), not checked unless specified
+    #[clap(long)]
+    pub min_apca: Option<f32>,
+    /// Highlight group to exclude from the checks, can be repeated
+    #[clap(long)]
+    pub ignore: Vec<String>,
+    /// Simulate color vision deficiencies and check that distinct groups such as DiffAdd and
+    /// DiffDelete can still be told apart
+    #[clap(long)]
+    pub cvd: bool,
+    /// Minimum perceptual difference (ΔE) between the colors of distinct groups
+    #[clap(long, default_value_t = 5.0)]
+    pub min_delta_e: f32,
+    /// List the standard Neovim highlight groups the theme leaves undefined
+    #[clap(long)]
+    pub coverage: bool,
+    /// Print the result of every check, not only the failing ones
+    #[clap(long)]
+    pub verbose: bool,
 }
 
 fn main() -> Result<(), anyhow::Error> {
     let args: Args = Args::parse();
 
-    let output = get_root_dir(args.output)?;
-    let theme = format::parse_theme(&args.filename)?;
+    match args.command {
+        Some(Command::Check(args)) => {
+            let theme = format::parse_theme(&args.filename)?;
+            print_warnings(&theme);
+            check::check(&theme, &args)?;
+        }
+        None => generate(
+            &args.filename.expect("Filename is required"),
+            args.output,
+            args.overwrite_init,
+            args.no_cterm,
+        )?,
+    }
+
+    Ok(())
+}
+
+fn generate(
+    filename: &str,
+    output: Option<String>,
+    overwrite_init: bool,
+    no_cterm: bool,
+) -> Result<(), anyhow::Error> {
+    let output = get_root_dir(output)?;
+    let mut theme = format::parse_theme(filename)?;
+    print_warnings(&theme);
+
+    if !no_cterm {
+        theme.highlights.iter_mut().for_each(cterm::apply_fallback);
+    }
 
     setup_directories(&output, &theme.name)?;
 
@@ -41,11 +123,17 @@ fn main() -> Result<(), anyhow::Error> {
 
     generate_palette_file(&output, &theme)?;
     generate_highlights_file(&output, &theme)?;
-    generate_init(&output, theme, args.overwrite_init)?;
+    generate_init(&output, theme, overwrite_init, no_cterm)?;
 
     Ok(())
 }
 
+fn print_warnings(theme: &Theme) {
+    for warning in &theme.warnings {
+        eprintln!("Warning: {warning}");
+    }
+}
+
 fn get_root_dir(output: Option<String>) -> Result<String, anyhow::Error> {
     Ok(match output {
         Some(root) => PathBuf::from(root),
@@ -139,7 +227,7 @@ function M.set_highlights()
     )?;
 
     for highlight in &theme.highlights {
-        writer.write_all(highlight.as_bytes())?;
+        writer.write_all(format!("\n{}", lua::render_highlight(highlight)).as_bytes())?;
     }
 
     writer.write_all(
@@ -151,7 +239,7 @@ function M.set_globals()
     )?;
 
     for global in &theme.globals {
-        writer.write_all(global.as_bytes())?;
+        writer.write_all(format!("{}\n", lua::render_global(global)).as_bytes())?;
     }
 
     writer.write_all(
@@ -163,9 +251,20 @@ return M\n",
     Ok(())
 }
 
-fn generate_init(output: &str, theme: Theme, overwrite_init: bool) -> Result<(), anyhow::Error> {
+fn generate_init(
+    output: &str,
+    theme: Theme,
+    overwrite_init: bool,
+    no_cterm: bool,
+) -> Result<(), anyhow::Error> {
     let name = &theme.name;
 
+    // Leave termguicolors to Neovim's detection unless there is no fallback to use without it
+    let termguicolors = match no_cterm {
+        true => "\n    vim.o.termguicolors = true",
+        false => "",
+    };
+
     let file_path = format!("{output}/lua/{name}/init.lua");
 
     if !overwrite_init && Path::new(&file_path).exists() {
@@ -193,8 +292,7 @@ function M.init(theme)
         vim.cmd(\"syntax reset\")
     end
 
-    vim.g.colors_name = \"{name}\"
-    vim.o.termguicolors = true
+    vim.g.colors_name = \"{name}\"{termguicolors}
 
     palette = require(string.format(\"{name}.highlights-%s\", vim.o.background))
     palette.set_highlights()
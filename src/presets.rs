@@ -0,0 +1,259 @@
+pub(crate) const ROLES: [&str; 26] = [
+    // Base
+    "fg",
+    "bg",
+    "surface",
+    "selection",
+    "border",
+    "muted",
+    "accent",
+    // Syntax
+    "comment",
+    "keyword",
+    "string",
+    "number",
+    "constant",
+    "function",
+    "type",
+    "variable",
+    "operator",
+    "punctuation",
+    "special",
+    // Diagnostics
+    "error",
+    "warning",
+    "info",
+    "hint",
+    "ok",
+    // Version control
+    "added",
+    "changed",
+    "removed",
+];
+
+/// Highlights in the positional form, with roles in place of colors.
+pub(crate) struct Preset {
+    pub name: &'static str,
+    pub highlights: &'static [(&'static str, &'static str)],
+}
+
+pub(crate) const PRESETS: [Preset; 7] = [
+    Preset {
+        name: "treesitter",
+        highlights: TREESITTER,
+    },
+    Preset {
+        name: "lsp",
+        highlights: LSP,
+    },
+    Preset {
+        name: "diagnostics",
+        highlights: DIAGNOSTICS,
+    },
+    Preset {
+        name: "gitsigns",
+        highlights: GITSIGNS,
+    },
+    Preset {
+        name: "telescope",
+        highlights: TELESCOPE,
+    },
+    Preset {
+        name: "cmp",
+        highlights: CMP,
+    },
+    Preset {
+        name: "statusline",
+        highlights: STATUSLINE,
+    },
+];
+
+const TREESITTER: &[(&str, &str)] = &[
+    ("@variable", "variable"),
+    ("@variable.builtin", "special"),
+    ("@variable.parameter", "variable"),
+    ("@variable.member", "variable"),
+    ("@constant", "constant"),
+    ("@constant.builtin", "constant"),
+    ("@constant.macro", "constant"),
+    ("@module", "type"),
+    ("@label", "keyword"),
+    ("@string", "string"),
+    ("@string.documentation", "comment"),
+    ("@string.regexp", "special"),
+    ("@string.escape", "special"),
+    ("@string.special", "special"),
+    ("@string.special.url", "special - u"),
+    ("@character", "string"),
+    ("@character.special", "special"),
+    ("@boolean", "constant"),
+    ("@number", "number"),
+    ("@number.float", "number"),
+    ("@type", "type"),
+    ("@type.builtin", "type"),
+    ("@type.definition", "type"),
+    ("@attribute", "special"),
+    ("@property", "variable"),
+    ("@function", "function"),
+    ("@function.builtin", "function"),
+    ("@function.call", "function"),
+    ("@function.macro", "special"),
+    ("@function.method", "function"),
+    ("@function.method.call", "function"),
+    ("@constructor", "type"),
+    ("@operator", "operator"),
+    ("@keyword", "keyword"),
+    ("@keyword.function", "keyword"),
+    ("@keyword.operator", "keyword"),
+    ("@keyword.import", "keyword"),
+    ("@keyword.return", "keyword"),
+    ("@keyword.exception", "keyword"),
+    ("@keyword.conditional", "keyword"),
+    ("@keyword.repeat", "keyword"),
+    ("@keyword.directive", "special"),
+    ("@punctuation.delimiter", "punctuation"),
+    ("@punctuation.bracket", "punctuation"),
+    ("@punctuation.special", "special"),
+    ("@comment", "comment - i"),
+    ("@comment.error", "error - b"),
+    ("@comment.warning", "warning - b"),
+    ("@comment.todo", "info - b"),
+    ("@comment.note", "hint - b"),
+    ("@markup.strong", "fg - b"),
+    ("@markup.italic", "fg - i"),
+    ("@markup.strikethrough", "fg - s"),
+    ("@markup.underline", "fg - u"),
+    ("@markup.heading", "keyword - b"),
+    ("@markup.quote", "comment - i"),
+    ("@markup.math", "special"),
+    ("@markup.link", "accent - u"),
+    ("@markup.link.url", "accent - u"),
+    ("@markup.raw", "string"),
+    ("@markup.list", "punctuation"),
+    ("@diff.plus", "added"),
+    ("@diff.minus", "removed"),
+    ("@diff.delta", "changed"),
+    ("@tag", "keyword"),
+    ("@tag.attribute", "variable"),
+    ("@tag.delimiter", "punctuation"),
+];
+
+const LSP: &[(&str, &str)] = &[
+    ("@lsp.type.class", "type"),
+    ("@lsp.type.comment", "comment"),
+    ("@lsp.type.decorator", "special"),
+    ("@lsp.type.enum", "type"),
+    ("@lsp.type.enumMember", "constant"),
+    ("@lsp.type.function", "function"),
+    ("@lsp.type.interface", "type"),
+    ("@lsp.type.macro", "special"),
+    ("@lsp.type.method", "function"),
+    ("@lsp.type.namespace", "type"),
+    ("@lsp.type.parameter", "variable"),
+    ("@lsp.type.property", "variable"),
+    ("@lsp.type.struct", "type"),
+    ("@lsp.type.type", "type"),
+    ("@lsp.type.typeParameter", "type"),
+    ("@lsp.type.variable", "variable"),
+    ("@lsp.mod.deprecated", "link:DiagnosticDeprecated"),
+    ("LspReferenceText", "- selection"),
+    ("LspReferenceRead", "- selection"),
+    ("LspReferenceWrite", "- selection"),
+    ("LspInlayHint", "muted"),
+];
+
+const DIAGNOSTICS: &[(&str, &str)] = &[
+    ("DiagnosticError", "error"),
+    ("DiagnosticWarn", "warning"),
+    ("DiagnosticInfo", "info"),
+    ("DiagnosticHint", "hint"),
+    ("DiagnosticOk", "ok"),
+    ("DiagnosticUnderlineError", "- - c error"),
+    ("DiagnosticUnderlineWarn", "- - c warning"),
+    ("DiagnosticUnderlineInfo", "- - c info"),
+    ("DiagnosticUnderlineHint", "- - c hint"),
+    ("DiagnosticUnderlineOk", "- - c ok"),
+    ("DiagnosticVirtualTextError", "error surface"),
+    ("DiagnosticVirtualTextWarn", "warning surface"),
+    ("DiagnosticVirtualTextInfo", "info surface"),
+    ("DiagnosticVirtualTextHint", "hint surface"),
+    ("DiagnosticVirtualTextOk", "ok surface"),
+    ("DiagnosticDeprecated", "muted - s"),
+    ("DiagnosticUnnecessary", "muted"),
+];
+
+const GITSIGNS: &[(&str, &str)] = &[
+    ("GitSignsAdd", "added"),
+    ("GitSignsChange", "changed"),
+    ("GitSignsDelete", "removed"),
+    ("GitSignsAddNr", "added"),
+    ("GitSignsChangeNr", "changed"),
+    ("GitSignsDeleteNr", "removed"),
+    ("GitSignsCurrentLineBlame", "muted - i"),
+];
+
+const TELESCOPE: &[(&str, &str)] = &[
+    ("TelescopeNormal", "fg surface"),
+    ("TelescopeBorder", "border surface"),
+    ("TelescopeTitle", "accent surface b"),
+    ("TelescopePromptPrefix", "accent surface"),
+    ("TelescopeSelection", "fg selection"),
+    ("TelescopeSelectionCaret", "accent selection"),
+    ("TelescopeMatching", "accent - b"),
+];
+
+const CMP: &[(&str, &str)] = &[
+    ("CmpItemAbbr", "fg"),
+    ("CmpItemAbbrDeprecated", "muted - s"),
+    ("CmpItemAbbrMatch", "accent - b"),
+    ("CmpItemAbbrMatchFuzzy", "accent"),
+    ("CmpItemMenu", "muted"),
+    ("CmpItemKind", "special"),
+    ("CmpItemKindFunction", "function"),
+    ("CmpItemKindMethod", "function"),
+    ("CmpItemKindVariable", "variable"),
+    ("CmpItemKindKeyword", "keyword"),
+    ("CmpItemKindText", "string"),
+];
+
+/// The statusline groups lualine derives its `auto` theme from.
+const STATUSLINE: &[(&str, &str)] = &[
+    ("StatusLine", "fg surface"),
+    ("StatusLineNC", "muted surface"),
+    ("TabLine", "muted surface"),
+    ("TabLineFill", "- surface"),
+    ("TabLineSel", "fg bg b"),
+    ("WinSeparator", "border"),
+];
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::{
+        color::{Color, RgbColor},
+        highlight::{parse_highlight, validate_links},
+    };
+
+    #[test]
+    fn test_presets_use_known_roles() {
+        let roles: IndexMap<String, Box<dyn Color>> = ROLES
+            .iter()
+            .map(|role| {
+                let color: Box<dyn Color> = Box::new(RgbColor::new(0, 0, 0));
+                (role.to_string(), color)
+            })
+            .collect();
+
+        for preset in &PRESETS {
+            let highlights = preset
+                .highlights
+                .iter()
+                .map(|(group, value)| parse_highlight(group, value, &roles).unwrap())
+                .collect::<Vec<_>>();
+
+            validate_links(&highlights).unwrap();
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+use crate::color::{Color, RgbColor};
+
+/// Color vision deficiencies, simulated with the matrices from Machado, Oliveira and Fernandes
+/// (2009) at full severity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Deficiency {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl Deficiency {
+    pub(crate) const ALL: [Deficiency; 3] = [
+        Deficiency::Protanopia,
+        Deficiency::Deuteranopia,
+        Deficiency::Tritanopia,
+    ];
+
+    fn matrix(self) -> [[f32; 3]; 3] {
+        match self {
+            Deficiency::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Deficiency::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Deficiency::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+
+    pub(crate) fn simulate(self, color: &dyn Color) -> RgbColor {
+        let linear = color.to_rgb().to_linear();
+
+        RgbColor::from_linear(
+            self.matrix()
+                .map(|row| row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2]),
+        )
+    }
+}
+
+impl fmt::Display for Deficiency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Deficiency::Protanopia => write!(f, "protanopia"),
+            Deficiency::Deuteranopia => write!(f, "deuteranopia"),
+            Deficiency::Tritanopia => write!(f, "tritanopia"),
+        }
+    }
+}
+
+/// Perceptual color difference as the euclidean distance in OKLab, scaled to roughly match the
+/// range of CIE ΔE where a difference of about 2 is barely noticeable.
+pub(crate) fn delta_e(color1: &dyn Color, color2: &dyn Color) -> f32 {
+    let [l1, a1, b1] = color1.to_rgb().to_oklab();
+    let [l2, a2, b2] = color2.to_rgb().to_oklab();
+
+    ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt() * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulate_gray_unchanged() {
+        let gray = RgbColor::parse_from_hex("#808080").unwrap();
+
+        for deficiency in Deficiency::ALL {
+            assert!(delta_e(&deficiency.simulate(&gray), &gray) < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_red_green_indistinguishable() {
+        let red = RgbColor::parse_from_hex("#b85450").unwrap();
+        let green = RgbColor::parse_from_hex("#6a8f3a").unwrap();
+
+        assert!(delta_e(&red, &green) > 10.0);
+        assert!(
+            delta_e(
+                &Deficiency::Deuteranopia.simulate(&red),
+                &Deficiency::Deuteranopia.simulate(&green)
+            ) < 10.0
+        );
+    }
+}